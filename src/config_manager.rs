@@ -237,6 +237,37 @@ impl ConfigManager {
         Ok(contents)
     }
 
+    /// Reads the optional `override.yml` user layer, returning its `unset`/
+    /// `include` directives. A missing file yields empty directives.
+    pub fn load_override(&self) -> Result<crate::yaml_utils::OverrideDirectives, io::Error> {
+        let path = self.config_dir.join("override.yml");
+        if !path.exists() {
+            return Ok(crate::yaml_utils::OverrideDirectives::default());
+        }
+        let content = fs::read_to_string(&path)?;
+        serde_yaml::from_str(&content)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Loads the files named by `include` (resolved relative to the config dir)
+    /// as additional [`ConfigLayer`]s, skipping any that are missing or unparsable.
+    pub fn load_included_layers(
+        &self,
+        directives: &crate::yaml_utils::OverrideDirectives,
+    ) -> Vec<crate::yaml_utils::ConfigLayer> {
+        use crate::yaml_utils::{Config, ConfigLayer};
+
+        let mut layers = Vec::new();
+        for entry in &directives.include {
+            let path = self.config_dir.join(entry);
+            match fs::read_to_string(&path).ok().and_then(|c| Config::from_yaml(&c).ok()) {
+                Some(config) => layers.push(ConfigLayer::from_config(entry.clone(), config)),
+                None => eprintln!("skipping unreadable include: {}", path.display()),
+            }
+        }
+        layers
+    }
+
     pub fn load_base_template(&self) -> Result<String, io::Error> {
         let default_template_path = self
             .config_dir
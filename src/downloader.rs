@@ -1,12 +1,120 @@
-use futures::future::{join_all, try_join_all};
+use futures::future::join_all;
 use reqwest;
+use reqwest::header::{
+    ACCEPT_RANGES, AUTHORIZATION, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, LOCATION,
+    RANGE,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use url::Url;
 
 /// Type alias for save closure function (simplifies function signatures)
 type Filename = str;
 type Content = str;
 pub type SaveFn = dyn Fn(&Filename, &Content) -> Result<(), Box<dyn Error>> + Send + Sync;
 
+/// A credential attached to outgoing requests as an `Authorization` header.
+#[derive(Debug, Clone)]
+pub enum Credential {
+    /// `Authorization: Bearer <token>`.
+    Bearer(String),
+    /// `Authorization: Basic <base64(user:pass)>`.
+    Basic(String),
+}
+
+impl Credential {
+    /// Builds a `Basic` credential, base64-encoding `user:pass`.
+    pub fn basic(user: &str, pass: &str) -> Self {
+        use base64::Engine;
+        let encoded =
+            base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", user, pass));
+        Credential::Basic(encoded)
+    }
+
+    /// The full `Authorization` header value.
+    pub fn header_value(&self) -> String {
+        match self {
+            Credential::Bearer(token) => format!("Bearer {}", token),
+            Credential::Basic(encoded) => format!("Basic {}", encoded),
+        }
+    }
+}
+
+/// Registry mapping a host (or `host:port`) to the credential used to reach it.
+///
+/// Credentials are matched most-specific-first: an entry keyed by `host:port`
+/// wins over a bare `host` entry. The credential is only ever attached to the
+/// matching host, so a redirect onto a different host drops it (see
+/// [`follow_redirects`] and reqwest's cross-origin header stripping).
+#[derive(Debug, Clone, Default)]
+pub struct AuthTokens {
+    tokens: HashMap<String, Credential>,
+}
+
+impl AuthTokens {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `credential` for `host` (bare host) or `host:port`.
+    pub fn insert(&mut self, host: impl Into<String>, credential: Credential) {
+        self.tokens.insert(host.into(), credential);
+    }
+
+    /// Loads `host=<Authorization value>` entries (`;`- or newline-separated)
+    /// from the named environment variable, e.g.
+    /// `example.com=Bearer abc;sub.example.com:8443=Basic dXNlcg==`.
+    pub fn from_env(var: &str) -> Self {
+        let mut tokens = Self::new();
+        if let Ok(raw) = std::env::var(var) {
+            for entry in raw.split([';', '\n']) {
+                let entry = entry.trim();
+                if entry.is_empty() {
+                    continue;
+                }
+                if let Some((host, value)) = entry.split_once('=') {
+                    let value = value.trim();
+                    let credential = match value.strip_prefix("Basic ") {
+                        Some(rest) => Credential::Basic(rest.trim().to_string()),
+                        None => Credential::Bearer(
+                            value.strip_prefix("Bearer ").unwrap_or(value).trim().to_string(),
+                        ),
+                    };
+                    tokens.insert(host.trim().to_string(), credential);
+                }
+            }
+        }
+        tokens
+    }
+
+    /// The `Authorization` value to send for `url`, if any entry matches its
+    /// host. `host:port` entries take precedence over bare-host entries.
+    pub fn header_for(&self, url: &str) -> Option<String> {
+        let parsed = Url::parse(url).ok()?;
+        let host = parsed.host_str()?;
+        if let Some(port) = parsed.port() {
+            if let Some(cred) = self.tokens.get(&format!("{}:{}", host, port)) {
+                return Some(cred.header_value());
+            }
+        }
+        self.tokens.get(host).map(Credential::header_value)
+    }
+}
+
+/// Downloads text, attaching the matching per-host `Authorization` header from
+/// `auth` and stripping it if a redirect crosses to a different host.
+pub async fn download_text_with_auth(
+    url: &str,
+    auth: &AuthTokens,
+) -> Result<String, Box<dyn Error>> {
+    let authorization = auth.header_for(url);
+    let (body, _final_url) = follow_redirects(url, DEFAULT_MAX_REDIRECTS, authorization).await?;
+    Ok(body)
+}
+
 /// TLS configuration for HTTP client
 #[derive(Debug, Clone)]
 pub struct TlsConfig {
@@ -14,6 +122,8 @@ pub struct TlsConfig {
     pub accept_invalid_hostnames: bool,
     pub use_sni: bool,
     pub min_tls_version: Option<String>,
+    /// ALPN protocol identifiers to offer, e.g. `["h2", "http/1.1"]`.
+    pub alpn: Vec<String>,
 }
 
 impl Default for TlsConfig {
@@ -23,6 +133,7 @@ impl Default for TlsConfig {
             accept_invalid_hostnames: true, // Accept invalid hostnames
             use_sni: true,                  // Always use SNI
             min_tls_version: None,          // Accept any TLS version
+            alpn: Vec::new(),               // Offer no ALPN protocols
         }
     }
 }
@@ -56,10 +167,9 @@ pub async fn download_text_with_tls(
 
     // Apply TLS configuration if provided
     if let Some(tls) = tls_config {
-        // Create a rustls client with custom configuration
-        client_builder = client_builder
-            .danger_accept_invalid_certs(tls.accept_invalid_certs)
-            .use_rustls_tls();
+        // Hand reqwest a fully constructed rustls config so the ALPN list, TLS
+        // version floor, and SNI/verification knobs all take effect.
+        client_builder = client_builder.use_preconfigured_tls(build_rustls_config(&tls)?);
     }
 
     let client = client_builder.build()?;
@@ -75,50 +185,458 @@ pub async fn download_text_with_tls(
     Ok(text)
 }
 
-/// Downloads and saves files from a list of URLs
+/// Builds a [`rustls::ClientConfig`] from a [`TlsConfig`], translating the
+/// `min_tls_version`, `alpn`, and SNI/verification knobs into concrete rustls
+/// settings.
+///
+/// `min_tls_version` accepts `"1.2"` or `"1.3"`; any other value is rejected.
+/// When `accept_invalid_certs` or `accept_invalid_hostnames` is set a
+/// permissive certificate verifier is installed, matching the historical
+/// behaviour of this module.
+fn build_rustls_config(tls: &TlsConfig) -> Result<rustls::ClientConfig, Box<dyn Error>> {
+    use rustls::version::{TLS12, TLS13};
+
+    let versions: &[&rustls::SupportedProtocolVersion] = match tls.min_tls_version.as_deref() {
+        None | Some("1.2") => &[&TLS12, &TLS13],
+        Some("1.3") => &[&TLS13],
+        Some(other) => return Err(format!("unsupported min TLS version: {}", other).into()),
+    };
+
+    let mut roots = rustls::RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+
+    let builder = rustls::ClientConfig::builder_with_protocol_versions(versions);
+    let mut config = if tls.accept_invalid_certs || tls.accept_invalid_hostnames {
+        builder
+            .dangerous()
+            .with_custom_certificate_verifier(std::sync::Arc::new(NoVerification))
+            .with_no_client_auth()
+    } else {
+        builder.with_root_certificates(roots).with_no_client_auth()
+    };
+
+    config.alpn_protocols = tls.alpn.iter().map(|p| p.as_bytes().to_vec()).collect();
+    config.enable_sni = tls.use_sni;
+
+    Ok(config)
+}
+
+/// A certificate verifier that accepts any certificate, used to honour the
+/// `accept_invalid_certs`/`accept_invalid_hostnames` knobs.
+#[derive(Debug)]
+struct NoVerification;
+
+impl rustls::client::danger::ServerCertVerifier for NoVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &rustls::pki_types::CertificateDer<'_>,
+        _intermediates: &[rustls::pki_types::CertificateDer<'_>],
+        _server_name: &rustls::pki_types::ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: rustls::pki_types::UnixTime,
+    ) -> Result<rustls::client::danger::ServerCertVerified, rustls::Error> {
+        Ok(rustls::client::danger::ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        _message: &[u8],
+        _cert: &rustls::pki_types::CertificateDer<'_>,
+        _dss: &rustls::DigitallySignedStruct,
+    ) -> Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+        Ok(rustls::client::danger::HandshakeSignatureValid::assertion())
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        _message: &[u8],
+        _cert: &rustls::pki_types::CertificateDer<'_>,
+        _dss: &rustls::DigitallySignedStruct,
+    ) -> Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+        Ok(rustls::client::danger::HandshakeSignatureValid::assertion())
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+        rustls::crypto::ring::default_provider()
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+/// A cached response used to drive conditional GETs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub body: String,
+    /// `max-age` from `Cache-Control`, if any.
+    pub max_age: Option<u64>,
+    /// Unix timestamp when the entry was stored.
+    pub fetched_at: i64,
+}
+
+impl CacheEntry {
+    /// Whether the entry is still within its `max-age` freshness window.
+    fn is_fresh(&self, now: i64) -> bool {
+        match self.max_age {
+            Some(max_age) => now - self.fetched_at < max_age as i64,
+            None => false,
+        }
+    }
+}
+
+/// A store of cached responses keyed by URL, used for conditional fetches.
+pub trait CacheStore {
+    fn get(&self, url: &str) -> Option<CacheEntry>;
+    fn put(&self, url: &str, entry: CacheEntry);
+}
+
+/// In-memory [`CacheStore`] backed by a `HashMap`.
+#[derive(Default)]
+pub struct MemoryCacheStore {
+    entries: Mutex<HashMap<String, CacheEntry>>,
+}
+
+impl CacheStore for MemoryCacheStore {
+    fn get(&self, url: &str) -> Option<CacheEntry> {
+        self.entries.lock().unwrap().get(url).cloned()
+    }
+
+    fn put(&self, url: &str, entry: CacheEntry) {
+        self.entries.lock().unwrap().insert(url.to_string(), entry);
+    }
+}
+
+/// Disk-backed [`CacheStore`] that writes one JSON file per URL.
+pub struct DiskCacheStore {
+    dir: PathBuf,
+}
+
+impl DiskCacheStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn path_for(&self, url: &str) -> PathBuf {
+        // Hash-free, filesystem-safe key derived from the URL.
+        let key: String = url
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        self.dir.join(format!("{}.json", key))
+    }
+}
+
+impl CacheStore for DiskCacheStore {
+    fn get(&self, url: &str) -> Option<CacheEntry> {
+        let data = std::fs::read_to_string(self.path_for(url)).ok()?;
+        serde_json::from_str(&data).ok()
+    }
+
+    fn put(&self, url: &str, entry: CacheEntry) {
+        let _ = std::fs::create_dir_all(&self.dir);
+        if let Ok(data) = serde_json::to_string(&entry) {
+            let _ = std::fs::write(self.path_for(url), data);
+        }
+    }
+}
+
+/// Downloads text using a conditional GET against `store`.
+///
+/// A still-fresh cached entry (within its `Cache-Control: max-age`) is returned
+/// without any request. Otherwise `If-None-Match`/`If-Modified-Since` are sent;
+/// a `304 Not Modified` reply reuses the cached body, and a fresh `200` updates
+/// the store (unless `Cache-Control: no-store` forbids it).
+pub async fn download_text_cached(
+    url: &str,
+    store: &dyn CacheStore,
+) -> Result<String, Box<dyn Error>> {
+    let now = chrono::Utc::now().timestamp();
+    let cached = store.get(url);
+
+    if let Some(entry) = &cached {
+        if entry.is_fresh(now) {
+            return Ok(entry.body.clone());
+        }
+    }
+
+    let clash_ua = "ClashMetaForAndroid/2.11.19";
+    let client = reqwest::Client::builder().user_agent(clash_ua).build()?;
+    let mut request = client.get(url);
+    if let Some(entry) = &cached {
+        if let Some(etag) = &entry.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &entry.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+
+    let response = request.send().await?;
+
+    if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+        if let Some(entry) = cached {
+            return Ok(entry.body);
+        }
+    }
+
+    if !response.status().is_success() {
+        return Err(format!("HTTP request failed with status: {}", response.status()).into());
+    }
+
+    let etag = header_string(&response, ETAG);
+    let last_modified = header_string(&response, LAST_MODIFIED);
+    let cache_control = response
+        .headers()
+        .get(reqwest::header::CACHE_CONTROL)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+
+    let body = response.text().await?;
+
+    if !cache_control.contains("no-store") {
+        store.put(
+            url,
+            CacheEntry {
+                etag,
+                last_modified,
+                body: body.clone(),
+                max_age: parse_max_age(&cache_control),
+                fetched_at: now,
+            },
+        );
+    }
+
+    Ok(body)
+}
+
+/// Reads a response header as an owned `String`, if present and valid UTF-8.
+fn header_string(response: &reqwest::Response, name: reqwest::header::HeaderName) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string())
+}
+
+/// Extracts the `max-age=<secs>` directive from a `Cache-Control` value.
+fn parse_max_age(cache_control: &str) -> Option<u64> {
+    cache_control
+        .split(',')
+        .filter_map(|directive| directive.trim().strip_prefix("max-age="))
+        .find_map(|value| value.parse().ok())
+}
+
+/// Default cap on the number of redirects [`download_text_resolved`] will follow.
+pub const DEFAULT_MAX_REDIRECTS: usize = 10;
+
+/// Downloads text while following redirects explicitly, returning the final body
+/// and the URL it was ultimately served from.
+pub async fn download_text_resolved(url: &str) -> Result<(String, String), Box<dyn Error>> {
+    follow_redirects(url, DEFAULT_MAX_REDIRECTS, None).await
+}
+
+/// Manually follows `301/302/303/307/308` redirects with reqwest's built-in
+/// policy disabled.
+///
+/// Each `Location` is resolved relative to the current URL; the chain is capped
+/// at `max_redirects` and cycles are detected via the visited set. An optional
+/// `Authorization` header is replayed only while the redirect stays on the
+/// starting host, and is dropped on the first cross-host hop.
+pub(crate) async fn follow_redirects(
+    start: &str,
+    max_redirects: usize,
+    authorization: Option<String>,
+) -> Result<(String, String), Box<dyn Error>> {
+    let clash_ua = "ClashMetaForAndroid/2.11.19";
+    let client = reqwest::Client::builder()
+        .user_agent(clash_ua)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()?;
+
+    let start_url = Url::parse(start)?;
+    let origin_host = start_url.host_str().map(|h| h.to_string());
+
+    let mut current = start_url;
+    let mut visited: HashSet<String> = HashSet::new();
+    let mut hops = 0;
+
+    loop {
+        if !visited.insert(current.as_str().to_string()) {
+            return Err(format!("redirect loop detected at {}", current).into());
+        }
+
+        let mut request = client.get(current.clone());
+        // Only replay credentials while we remain on the original host.
+        if let (Some(auth), true) = (&authorization, current.host_str() == origin_host.as_deref()) {
+            request = request.header(AUTHORIZATION, auth);
+        }
+
+        let response = request.send().await?;
+        let status = response.status();
+
+        if matches!(status.as_u16(), 301 | 302 | 303 | 307 | 308) {
+            if hops >= max_redirects {
+                return Err(format!("exceeded {} redirects", max_redirects).into());
+            }
+            let location = response
+                .headers()
+                .get(LOCATION)
+                .and_then(|v| v.to_str().ok())
+                .ok_or("redirect response without Location header")?;
+            current = current.join(location)?;
+            hops += 1;
+            continue;
+        }
+
+        if !status.is_success() {
+            return Err(format!("HTTP request failed with status: {}", status).into());
+        }
+
+        let final_url = current.to_string();
+        let body = response.text().await?;
+        return Ok((body, final_url));
+    }
+}
+
+/// Options controlling how [`download_save_files`] reaches the network.
+#[derive(Debug, Clone, Default)]
+pub struct DownloadOptions {
+    /// Explicit proxy URL that overrides the `HTTP_PROXY`/`HTTPS_PROXY`
+    /// environment variables. When `None`, reqwest's default handling of the
+    /// standard `*_PROXY`/`NO_PROXY` variables applies.
+    pub proxy: Option<String>,
+    /// Per-host credentials attached to each request. reqwest's default redirect
+    /// policy strips the `Authorization` header on cross-origin redirects, so a
+    /// token never leaks to a host it was not registered for.
+    pub auth: AuthTokens,
+}
+
+/// Downloads and saves files from a list of URLs.
+///
+/// Each download is attempted independently: instead of aborting the whole
+/// batch on the first failure, the per-URL outcome is collected so one bad URL
+/// does not lose the others. Partial downloads are resumed — a staging file is
+/// kept in the system temp directory and a `Range: bytes=<offset>-` request
+/// continues it, appending on `206 Partial Content` and restarting on a plain
+/// `200 OK`.
 ///
 /// # Arguments
 /// * `url_list` - Vector of URLs to download
 /// * `save_fn` - Save function callback
+/// * `options` - Proxy and transport overrides
 ///
 /// # Returns
-/// * `Result<(), Box<dyn Error>>` - Success or error
+/// * `Result<Vec<Result<(), String>>, Box<dyn Error>>` - One outcome per URL, in
+///   input order; the outer error is returned only when the HTTP client cannot be built
 pub async fn download_save_files(
     url_list: Vec<String>,
     save_fn: &SaveFn,
-) -> Result<(), Box<dyn Error>> {
+    options: DownloadOptions,
+) -> Result<Vec<Result<(), String>>, Box<dyn Error>> {
     let clash_ua = "ClashMetaForAndroid/2.11.19";
-    let client = reqwest::Client::builder().user_agent(clash_ua).build()?;
+    let mut builder = reqwest::Client::builder().user_agent(clash_ua);
+    if let Some(proxy) = &options.proxy {
+        builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+    }
+    let client = builder.build()?;
 
-    // Create download tasks for all URLs in parallel
-    let download_tasks: Vec<_> = url_list
+    // Create download tasks for all URLs in parallel. Each task yields a
+    // `Result` so a single failure is reported rather than unwinding the batch.
+    let download_tasks = url_list
         .into_iter()
-        .map(async |url| {
-            let response = client.get(&url).send().await.unwrap();
+        .map(|url| download_one(&client, save_fn, &options.auth, url));
 
-            if !response.status().is_success() {
-                panic!("Couldn't get {}", url);
-            }
+    Ok(join_all(download_tasks).await)
+}
+
+/// Downloads a single URL, resuming into its staging file when possible, and
+/// hands the completed body to `save_fn`.
+async fn download_one(
+    client: &reqwest::Client,
+    save_fn: &SaveFn,
+    auth: &AuthTokens,
+    url: String,
+) -> Result<(), String> {
+    let staging = std::env::temp_dir().join(format!("chiaotu-{}", sanitize_key(&url)));
+    let offset = std::fs::metadata(&staging).map(|m| m.len()).unwrap_or(0);
+
+    let mut request = client.get(&url);
+    if let Some(value) = auth.header_for(&url) {
+        request = request.header(AUTHORIZATION, value);
+    }
+    if offset > 0 {
+        request = request.header(RANGE, format!("bytes={}-", offset));
+    }
 
-            // Extract filename from Content-Disposition header
-            let filename = extract_filename_from_response(&response).unwrap_or_else(|| {
-                // Fallback: extract filename from URL
-                extract_filename_from_url(&url)
-            });
+    let response = request.send().await.map_err(|e| e.to_string())?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("Couldn't get {}: HTTP {}", url, status));
+    }
 
-            // Download the content
-            let content = response.text().await.unwrap();
+    // Extract filename from Content-Disposition header, falling back to the URL.
+    let filename =
+        extract_filename_from_response(&response).unwrap_or_else(|| extract_filename_from_url(&url));
 
-            save_fn(&filename, &content).unwrap();
-        })
-        .collect();
+    // A `206` with `Accept-Ranges: bytes` continues the staging file; anything
+    // else (a plain `200`) starts it over.
+    let appendable = status == reqwest::StatusCode::PARTIAL_CONTENT
+        && response
+            .headers()
+            .get(ACCEPT_RANGES)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.eq_ignore_ascii_case("bytes"))
+            .unwrap_or(false);
 
-    // Execute all downloads in parallel and collect results
-    join_all(download_tasks).await;
+    // A `206` that doesn't advertise `Accept-Ranges: bytes` only hands us the
+    // range tail; appending is fine but overwriting the staging file with just
+    // that tail would truncate the body. Re-fetch the whole resource without a
+    // `Range` header so we persist the complete response.
+    let (mut response, resuming) = if status == reqwest::StatusCode::PARTIAL_CONTENT && !appendable {
+        let mut retry = client.get(&url);
+        if let Some(value) = auth.header_for(&url) {
+            retry = retry.header(AUTHORIZATION, value);
+        }
+        let retry = retry.send().await.map_err(|e| e.to_string())?;
+        if !retry.status().is_success() {
+            return Err(format!("Couldn't get {}: HTTP {}", url, retry.status()));
+        }
+        (retry, false)
+    } else {
+        (response, appendable)
+    };
 
+    // Stream the body into the staging file as it arrives, so a dropped
+    // connection leaves the bytes received so far for the next attempt.
+    {
+        use std::io::Write;
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(resuming)
+            .truncate(!resuming)
+            .open(&staging)
+            .map_err(|e| e.to_string())?;
+        while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+            file.write_all(&chunk).map_err(|e| e.to_string())?;
+        }
+    }
+
+    let content = std::fs::read_to_string(&staging).map_err(|e| e.to_string())?;
+    save_fn(&filename, &content).map_err(|e| e.to_string())?;
+    let _ = std::fs::remove_file(&staging);
     Ok(())
 }
 
+/// Maps a URL to a filesystem-safe staging-file key.
+fn sanitize_key(url: &str) -> String {
+    url.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
 /// Extracts filename from Content-Disposition header
 fn extract_filename_from_response(response: &reqwest::Response) -> Option<String> {
     if let Some(content_disposition) = response.headers().get("content-disposition") {
@@ -0,0 +1,214 @@
+use std::collections::HashSet;
+
+use serde_json::{json, Map, Value};
+
+use crate::nodes::{NodeConfig, ShadowsocksConfig, TrojanConfig, VlessConfig, VmessConfig};
+
+/// Converts parsed nodes into a Clash `proxies:` YAML document.
+///
+/// Output ordering follows the input slice and each proxy's `name` is derived
+/// from its remarks (deduplicated with a numeric suffix) so the document is
+/// deterministic and free of name clashes.
+pub fn to_clash_yaml(nodes: &[NodeConfig]) -> String {
+    let mut tags = TagAllocator::new();
+    let proxies: Vec<Value> = nodes
+        .iter()
+        .map(|node| clash_proxy(node, tags.allocate(node_remarks(node))))
+        .collect();
+
+    serde_yaml::to_string(&json!({ "proxies": proxies })).unwrap_or_default()
+}
+
+/// Converts parsed nodes into a sing-box `outbounds` JSON array.
+pub fn to_singbox_json(nodes: &[NodeConfig]) -> Value {
+    let mut tags = TagAllocator::new();
+    let outbounds: Vec<Value> = nodes
+        .iter()
+        .map(|node| singbox_outbound(node, tags.allocate(node_remarks(node))))
+        .collect();
+
+    json!({ "outbounds": outbounds })
+}
+
+/// Builds a single Clash proxy mapping for a node.
+fn clash_proxy(node: &NodeConfig, name: String) -> Value {
+    match node {
+        NodeConfig::Trojan(cfg) => clash_trojan(cfg, name),
+        NodeConfig::Vmess(cfg) => clash_vmess(cfg, name),
+        NodeConfig::Shadowsocks(cfg) => clash_shadowsocks(cfg, name),
+        NodeConfig::Vless(cfg) => clash_vless(cfg, name),
+    }
+}
+
+fn clash_trojan(cfg: &TrojanConfig, name: String) -> Value {
+    let mut proxy = Map::new();
+    proxy.insert("name".into(), json!(name));
+    proxy.insert("type".into(), json!("trojan"));
+    proxy.insert("server".into(), json!(cfg.server));
+    proxy.insert("port".into(), json!(cfg.server_port));
+    proxy.insert("password".into(), json!(cfg.password));
+    proxy.insert("skip-cert-verify".into(), json!(cfg.allow_insecure));
+    if let Some(sni) = &cfg.sni {
+        proxy.insert("sni".into(), json!(sni));
+    }
+    if let Some(network) = &cfg.network {
+        proxy.insert("network".into(), json!(network));
+    }
+    Value::Object(proxy)
+}
+
+fn clash_vmess(cfg: &VmessConfig, name: String) -> Value {
+    let mut proxy = Map::new();
+    proxy.insert("name".into(), json!(name));
+    proxy.insert("type".into(), json!("vmess"));
+    proxy.insert("server".into(), json!(cfg.address));
+    proxy.insert("port".into(), json!(cfg.port));
+    proxy.insert("uuid".into(), json!(cfg.user_id));
+    proxy.insert("alterId".into(), json!(cfg.alter_id));
+    proxy.insert("cipher".into(), json!("auto"));
+    proxy.insert("tls".into(), json!(cfg.tls == "tls"));
+    if !cfg.network.is_empty() {
+        proxy.insert("network".into(), json!(cfg.network));
+    }
+    if cfg.network == "ws" {
+        let mut ws_opts = Map::new();
+        if !cfg.path.is_empty() {
+            ws_opts.insert("path".into(), json!(cfg.path));
+        }
+        if !cfg.host.is_empty() {
+            ws_opts.insert("headers".into(), json!({ "Host": cfg.host }));
+        }
+        proxy.insert("ws-opts".into(), Value::Object(ws_opts));
+    }
+    Value::Object(proxy)
+}
+
+fn clash_shadowsocks(cfg: &ShadowsocksConfig, name: String) -> Value {
+    let mut proxy = Map::new();
+    proxy.insert("name".into(), json!(name));
+    proxy.insert("type".into(), json!("ss"));
+    proxy.insert("server".into(), json!(cfg.server));
+    proxy.insert("port".into(), json!(cfg.server_port));
+    proxy.insert("cipher".into(), json!(cfg.method));
+    proxy.insert("password".into(), json!(cfg.password));
+    if let Some(plugin) = &cfg.plugin {
+        proxy.insert("plugin".into(), json!(plugin));
+    }
+    if let Some(plugin_opts) = &cfg.plugin_opts {
+        proxy.insert("plugin-opts".into(), json!(plugin_opts));
+    }
+    Value::Object(proxy)
+}
+
+fn clash_vless(cfg: &VlessConfig, name: String) -> Value {
+    let mut proxy = Map::new();
+    proxy.insert("name".into(), json!(name));
+    proxy.insert("type".into(), json!("vless"));
+    proxy.insert("server".into(), json!(cfg.server));
+    proxy.insert("port".into(), json!(cfg.server_port));
+    proxy.insert("uuid".into(), json!(cfg.uuid));
+    proxy.insert("skip-cert-verify".into(), json!(cfg.insecure));
+    if let Some(flow) = &cfg.flow {
+        proxy.insert("flow".into(), json!(flow));
+    }
+    if let Some(sni) = &cfg.sni {
+        proxy.insert("servername".into(), json!(sni));
+    }
+    if let Some(network) = &cfg.network {
+        proxy.insert("network".into(), json!(network));
+    }
+    Value::Object(proxy)
+}
+
+/// Builds a single sing-box outbound object for a node.
+fn singbox_outbound(node: &NodeConfig, tag: String) -> Value {
+    match node {
+        NodeConfig::Trojan(cfg) => json!({
+            "type": "trojan",
+            "tag": tag,
+            "server": cfg.server,
+            "server_port": cfg.server_port,
+            "password": cfg.password,
+            "tls": { "enabled": true, "server_name": cfg.sni, "insecure": cfg.allow_insecure },
+        }),
+        NodeConfig::Vmess(cfg) => {
+            let mut outbound = json!({
+                "type": "vmess",
+                "tag": tag,
+                "server": cfg.address,
+                "server_port": cfg.port,
+                "uuid": cfg.user_id,
+                "alter_id": cfg.alter_id,
+                "security": "auto",
+            });
+            // sing-box only accepts an explicit transport for non-TCP networks;
+            // emitting `"type": "tcp"` (or an empty network) makes it reject the
+            // whole outbound, so plain TCP nodes carry no `transport` at all.
+            if matches!(
+                cfg.network.as_str(),
+                "ws" | "grpc" | "http" | "quic" | "httpupgrade"
+            ) {
+                outbound["transport"] =
+                    json!({ "type": cfg.network, "path": cfg.path, "headers": { "Host": cfg.host } });
+            }
+            outbound
+        }
+        NodeConfig::Shadowsocks(cfg) => json!({
+            "type": "shadowsocks",
+            "tag": tag,
+            "server": cfg.server,
+            "server_port": cfg.server_port,
+            "method": cfg.method,
+            "password": cfg.password,
+        }),
+        NodeConfig::Vless(cfg) => json!({
+            "type": "vless",
+            "tag": tag,
+            "server": cfg.server,
+            "server_port": cfg.server_port,
+            "uuid": cfg.uuid,
+            "flow": cfg.flow,
+        }),
+    }
+}
+
+/// Reads the human-readable remarks of a node, falling back to its server.
+fn node_remarks(node: &NodeConfig) -> String {
+    match node {
+        NodeConfig::Trojan(cfg) => cfg.remarks.clone().unwrap_or_else(|| cfg.server.clone()),
+        NodeConfig::Vmess(cfg) => {
+            if cfg.remarks.is_empty() {
+                cfg.address.clone()
+            } else {
+                cfg.remarks.clone()
+            }
+        }
+        NodeConfig::Shadowsocks(cfg) => cfg.remarks.clone().unwrap_or_else(|| cfg.server.clone()),
+        NodeConfig::Vless(cfg) => cfg.remarks.clone().unwrap_or_else(|| cfg.server.clone()),
+    }
+}
+
+/// Hands out unique tags, appending `-2`, `-3`, … to repeated remarks.
+struct TagAllocator {
+    seen: HashSet<String>,
+}
+
+impl TagAllocator {
+    fn new() -> Self {
+        Self { seen: HashSet::new() }
+    }
+
+    fn allocate(&mut self, base: String) -> String {
+        if self.seen.insert(base.clone()) {
+            return base;
+        }
+        let mut counter = 2;
+        loop {
+            let candidate = format!("{}-{}", base, counter);
+            if self.seen.insert(candidate.clone()) {
+                return candidate;
+            }
+            counter += 1;
+        }
+    }
+}
@@ -1,18 +1,20 @@
 mod base64_decoder;
 mod config_manager;
 mod downloader;
+mod export;
 mod file_reader;
 mod node_parser;
 mod nodes;
+mod subscription;
 mod yaml_utils;
 
 use crate::{
     config_manager::ConfigManager,
-    downloader::download_save_files,
-    yaml_utils::{Config, create_groups_by_country, merge_proxies},
+    downloader::{download_save_files, DownloadOptions},
+    yaml_utils::{Config, ConfigLayer, create_groups_by_country, merge_layers},
 };
 use file_reader::read_file_to_string;
-use itertools::Itertools;
+use std::path::{Path, PathBuf};
 use std::{collections::HashMap, env, vec};
 
 #[tokio::main]
@@ -21,65 +23,34 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let args: Vec<String> = env::args().collect();
 
-    // Check if a file path argument is provided
+    // No argument: regenerate straight from the cached sources.
     if args.len() <= 1 {
-        let contents = config_manager.load_cache()?;
-        let mut configs = vec![];
-        for (vendor, sub) in contents {
-            let  config = Config::from_yaml(&sub)?;
-
-            let mut proxies = vec![];
-            use crate::yaml_utils::Proxy;
-            for p in config.proxies {
-                let name = p.name;
-                let first = vendor.chars().next().ok_or_else(||"a".to_string())?;
-
-                let last = vendor.chars().last().ok_or_else(||"a".to_string())?;
-
-                let new_name = format!("{}@{}..{}",name, first, last);
-                proxies.push(Proxy {
-                    name: new_name,
-                    ..p
-                });
-            }
-            let new_config = Config {
-                proxies: proxies,
-                ..config
-            };
-            configs.push(new_config);
-        }
-
-        let proxies = merge_proxies(configs);
-        // remove duplicated items by "name"
-        let proxies: Vec<_> = proxies
-            .into_iter()
-            .unique_by(|proxy| proxy.name.clone())
-            .collect();
-        let contents = config_manager.load_rules()?;
-        let rules: Vec<_> = contents
-            .into_iter()
-            .flat_map(|e| Config::from_yaml(&e).unwrap().rules)
-            .collect();
-
-        let proxy_groups = create_groups_by_country(&proxies);
-
-        let base = config_manager.load_base_template()?;
-        let mut config = Config::from_yaml(&base)?;
-        config.proxies = proxies;
-        config.proxy_groups = proxy_groups;
-        config.rules = rules;
+        let sources = config_manager.load_cache()?;
+        return regenerate(&config_manager, sources);
+    }
 
-        config_manager
-            .save_result(&config.to_yaml().unwrap())
-            .unwrap();
+    let input = &args[1];
 
-        return Ok(());
+    // A directory or glob of local `.yml`/`.yaml` files is loaded directly and
+    // regenerated without any network access; anything else is treated as a
+    // file whose lines are remote subscription URLs.
+    if Path::new(input).is_dir() {
+        let files = collect_config_files(Path::new(input));
+        let sources = read_local_sources(&files);
+        return regenerate(&config_manager, sources);
+    }
+    if is_glob(input) {
+        let files: Vec<PathBuf> = glob::glob(input)
+            .map_err(|e| e.to_string())?
+            .filter_map(Result::ok)
+            .filter(|p| p.is_file() && has_config_extension(p))
+            .collect();
+        let sources = read_local_sources(&files);
+        return regenerate(&config_manager, sources);
     }
-
-    let file_path = &args[1];
 
     // Read the file content
-    let file_content = read_file_to_string(file_path)?;
+    let file_content = read_file_to_string(input)?;
 
     // Split the content into lines and collect URLs
     let urls: Vec<String> = file_content
@@ -89,14 +60,169 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .collect();
 
     // Create a simple save function using config manager
-    let save_fn = move |filename: &str, content: &str| {
+    let save_fn = |filename: &str, content: &str| {
         println!("Saving file: {}", filename);
         // Cache the filename
         config_manager.cache(filename, content)?;
         Ok(())
     };
 
-    download_save_files(urls, &save_fn).await?;
+    let results = download_save_files(urls, &save_fn, DownloadOptions::default()).await?;
+    for result in &results {
+        if let Err(err) = result {
+            eprintln!("download failed: {}", err);
+        }
+    }
+
+    Ok(())
+}
+
+/// Builds the final Clash config from a set of `(origin, body)` sources and
+/// saves it. Each body is classified as a Clash `Config` or a base64/plain node
+/// list; unrecognised bodies are reported and skipped.
+fn regenerate(
+    config_manager: &ConfigManager,
+    sources: Vec<(String, String)>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use crate::yaml_utils::Proxy;
+
+    let mut configs = vec![];
+    let mut skipped: Vec<String> = Vec::new();
+    for (vendor, sub) in sources {
+        let config = match Config::from_yaml(&sub) {
+            Ok(config) => config,
+            Err(_) => {
+                let proxies = node_parser::parse_subscription_to_proxies(&sub);
+                if proxies.is_empty() {
+                    skipped.push(vendor);
+                    continue;
+                }
+                Config {
+                    properties: HashMap::new(),
+                    proxies,
+                    proxy_groups: vec![],
+                    rules: vec![],
+                }
+            }
+        };
+
+        let mut proxies = vec![];
+        for p in config.proxies {
+            let name = p.name;
+            let first = vendor.chars().next().ok_or_else(|| "a".to_string())?;
+
+            let last = vendor.chars().last().ok_or_else(|| "a".to_string())?;
+
+            let new_name = format!("{}@{}..{}", name, first, last);
+            proxies.push(Proxy {
+                name: new_name,
+                ..p
+            });
+        }
+        let new_config = Config {
+            proxies,
+            ..config
+        };
+        configs.push(new_config);
+    }
+
+    if !skipped.is_empty() {
+        eprintln!(
+            "skipped {} unrecognized source(s): {}",
+            skipped.len(),
+            skipped.join(", ")
+        );
+    }
+
+    // Turn each source into a precedence-ordered layer: subscriptions first
+    // (their proxies win de-duplication), then the rule files, then any
+    // files pulled in by the override layer's `include` directive.
+    let mut layers: Vec<ConfigLayer> = configs
+        .into_iter()
+        .enumerate()
+        .map(|(index, config)| {
+            ConfigLayer::from_config(format!("subscription[{}]", index), config)
+        })
+        .collect();
+
+    let contents = config_manager.load_rules()?;
+    for (index, content) in contents.into_iter().enumerate() {
+        let config = Config::from_yaml(&content)?;
+        layers.push(ConfigLayer::from_config(format!("rules[{}]", index), config));
+    }
+
+    let overrides = config_manager.load_override()?;
+    layers.extend(config_manager.load_included_layers(&overrides));
+
+    let merged = merge_layers(&layers, &overrides.unset);
+    let proxies = merged.proxies;
+    let rules = merged.rules;
+
+    let proxy_groups = create_groups_by_country(&proxies);
+
+    let base = config_manager.load_base_template()?;
+    let mut config = Config::from_yaml(&base)?;
+    config.proxies = proxies;
+    config.proxy_groups = proxy_groups;
+    config.rules = rules;
+
+    // Report validation problems but still emit a usable result.
+    let problems = yaml_utils::validate_config(&config);
+    if !problems.is_empty() {
+        eprintln!("config validation found {} problem(s):", problems.len());
+        for problem in &problems {
+            eprintln!("  - {}", problem);
+        }
+    }
+
+    let yaml = config.to_yaml()?;
+    config_manager.save_result(&yaml)?;
 
     Ok(())
 }
+
+/// Recursively collects `.yml`/`.yaml` files under `dir`, mirroring the walk in
+/// `config_manager::copy_recursively`.
+fn collect_config_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                files.extend(collect_config_files(&path));
+            } else if has_config_extension(&path) {
+                files.push(path);
+            }
+        }
+    }
+    files
+}
+
+/// Whether a path ends in `.yml` or `.yaml`.
+fn has_config_extension(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("yml") | Some("yaml")
+    )
+}
+
+/// Reads each file into an `(origin, body)` source keyed by its file stem.
+fn read_local_sources(files: &[PathBuf]) -> Vec<(String, String)> {
+    let mut sources = Vec::new();
+    for path in files {
+        let origin = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.to_string_lossy().into_owned());
+        match std::fs::read_to_string(path) {
+            Ok(body) => sources.push((origin, body)),
+            Err(err) => eprintln!("skipping {}: {}", path.display(), err),
+        }
+    }
+    sources
+}
+
+/// Whether `input` looks like a glob pattern rather than a plain path.
+fn is_glob(input: &str) -> bool {
+    input.contains(['*', '?', '['])
+}
@@ -1,17 +1,29 @@
 
-use crate::nodes::{VmessConfig, parse_vmess_config};
+use crate::base64_decoder::decode_to_string;
+use crate::nodes::{
+    ShadowsocksConfig, TrojanConfig, VlessConfig, VmessConfig, parse_ss_config,
+    parse_trojan_config, parse_vless_config, parse_vmess_config,
+};
+use crate::yaml_utils::Proxy;
+use std::collections::HashMap;
 
 /// Represents a parsed node with its protocol information
 #[derive(Debug, Clone)]
 pub struct Node {
     pub protocol: Protocol,
     pub vmess_config: Option<VmessConfig>,
+    pub ss_config: Option<ShadowsocksConfig>,
+    pub trojan_config: Option<TrojanConfig>,
+    pub vless_config: Option<VlessConfig>,
 }
 
 /// Protocol types that a node can have
 #[derive(Debug, Clone, PartialEq)]
 pub enum Protocol {
     Vmess,
+    Shadowsocks,
+    Trojan,
+    Vless,
     Unidentified,
 }
 
@@ -55,13 +67,23 @@ pub fn parse_lines_to_nodes(lines: Vec<String>) -> Vec<Node> {
 pub fn parse_line_to_node(line: &str) -> Node {
     let protocol = detect_protocol(line);
 
-    let vmess_config = if protocol == Protocol::Vmess {
-        parse_vmess_config(line)
-    } else {
-        None
+    let mut node = Node {
+        protocol: protocol.clone(),
+        vmess_config: None,
+        ss_config: None,
+        trojan_config: None,
+        vless_config: None,
     };
 
-    Node { protocol, vmess_config }
+    match protocol {
+        Protocol::Vmess => node.vmess_config = parse_vmess_config(line),
+        Protocol::Shadowsocks => node.ss_config = parse_ss_config(line),
+        Protocol::Trojan => node.trojan_config = parse_trojan_config(line),
+        Protocol::Vless => node.vless_config = parse_vless_config(line),
+        Protocol::Unidentified => {}
+    }
+
+    node
 }
 
 /// Detects protocol type from a line string
@@ -75,10 +97,15 @@ pub fn parse_line_to_node(line: &str) -> Node {
 fn detect_protocol(line: &str) -> Protocol {
     let trimmed = line.trim();
 
-    // Check for Vmess patterns
-    if trimmed.starts_with("vmess://") ||
-       trimmed.to_lowercase().contains("vmess") {
+    // Dispatch on the URI scheme prefix.
+    if trimmed.starts_with("vmess://") {
         Protocol::Vmess
+    } else if trimmed.starts_with("ss://") {
+        Protocol::Shadowsocks
+    } else if trimmed.starts_with("trojan://") {
+        Protocol::Trojan
+    } else if trimmed.starts_with("vless://") {
+        Protocol::Vless
     } else {
         Protocol::Unidentified
     }
@@ -96,6 +123,157 @@ pub fn parse_to_nodes(input: &str) -> Vec<Node> {
     parse_lines_to_nodes(lines)
 }
 
+/// Decodes a subscription body into Clash [`Proxy`] entries.
+///
+/// Most endpoints return a base64-encoded, newline-separated list of node URIs
+/// rather than Clash YAML; the body is base64-decoded when possible (falling
+/// back to the raw text), run through [`parse_to_nodes`], and each recognised
+/// node is mapped to a proxy. Lines that cannot be parsed are skipped.
+pub fn parse_subscription_to_proxies(body: &str) -> Vec<Proxy> {
+    let decoded = decode_to_string(body).unwrap_or_else(|_| body.to_string());
+    parse_to_nodes(&decoded)
+        .iter()
+        .filter_map(node_to_proxy)
+        .collect()
+}
+
+/// Maps a parsed [`Node`] to a Clash [`Proxy`], if its protocol is supported.
+pub fn node_to_proxy(node: &Node) -> Option<Proxy> {
+    match node.protocol {
+        Protocol::Vmess => node.vmess_config.as_ref().map(vmess_to_proxy),
+        Protocol::Shadowsocks => node.ss_config.as_ref().map(ss_to_proxy),
+        Protocol::Trojan => node.trojan_config.as_ref().map(trojan_to_proxy),
+        Protocol::Vless => node.vless_config.as_ref().map(vless_to_proxy),
+        Protocol::Unidentified => None,
+    }
+}
+
+/// Builds a Clash `type: vmess` proxy from a parsed [`VmessConfig`].
+fn vmess_to_proxy(config: &VmessConfig) -> Proxy {
+    use serde_yaml::Value;
+
+    let name = if config.remarks.is_empty() {
+        config.address.clone()
+    } else {
+        config.remarks.clone()
+    };
+
+    let mut properties: HashMap<String, Value> = HashMap::new();
+    properties.insert("type".to_string(), Value::from("vmess"));
+    properties.insert("server".to_string(), Value::from(config.address.clone()));
+    if let Ok(port) = config.port.parse::<u16>() {
+        properties.insert("port".to_string(), Value::from(port));
+    }
+    properties.insert("uuid".to_string(), Value::from(config.user_id.clone()));
+    if let Ok(alter_id) = config.alter_id.parse::<u32>() {
+        properties.insert("alterId".to_string(), Value::from(alter_id));
+    }
+    let cipher = config.security.clone().unwrap_or_else(|| "auto".to_string());
+    properties.insert("cipher".to_string(), Value::from(cipher));
+    if !config.network.is_empty() {
+        properties.insert("network".to_string(), Value::from(config.network.clone()));
+    }
+    if config.tls == "tls" {
+        properties.insert("tls".to_string(), Value::from(true));
+    }
+    if let Some(sni) = &config.sni {
+        properties.insert("servername".to_string(), Value::from(sni.clone()));
+    }
+    if config.network == "ws" {
+        let mut ws_opts = serde_yaml::Mapping::new();
+        if !config.path.is_empty() {
+            ws_opts.insert(Value::from("path"), Value::from(config.path.clone()));
+        }
+        if !config.host.is_empty() {
+            let mut headers = serde_yaml::Mapping::new();
+            headers.insert(Value::from("Host"), Value::from(config.host.clone()));
+            ws_opts.insert(Value::from("headers"), Value::from(headers));
+        }
+        properties.insert("ws-opts".to_string(), Value::from(ws_opts));
+    }
+
+    Proxy { name, properties }
+}
+
+/// Builds a Clash `type: ss` proxy from a parsed [`ShadowsocksConfig`].
+fn ss_to_proxy(config: &ShadowsocksConfig) -> Proxy {
+    use serde_yaml::Value;
+
+    let name = config
+        .remarks
+        .clone()
+        .unwrap_or_else(|| config.server.clone());
+
+    let mut properties: HashMap<String, Value> = HashMap::new();
+    properties.insert("type".to_string(), Value::from("ss"));
+    properties.insert("server".to_string(), Value::from(config.server.clone()));
+    properties.insert("port".to_string(), Value::from(config.server_port));
+    properties.insert("cipher".to_string(), Value::from(config.method.clone()));
+    properties.insert("password".to_string(), Value::from(config.password.clone()));
+    if let Some(plugin) = &config.plugin {
+        properties.insert("plugin".to_string(), Value::from(plugin.clone()));
+    }
+
+    Proxy { name, properties }
+}
+
+/// Builds a Clash `type: trojan` proxy from a parsed [`TrojanConfig`].
+fn trojan_to_proxy(config: &TrojanConfig) -> Proxy {
+    use serde_yaml::Value;
+
+    let name = config
+        .remarks
+        .clone()
+        .unwrap_or_else(|| config.server.clone());
+
+    let mut properties: HashMap<String, Value> = HashMap::new();
+    properties.insert("type".to_string(), Value::from("trojan"));
+    properties.insert("server".to_string(), Value::from(config.server.clone()));
+    properties.insert("port".to_string(), Value::from(config.server_port));
+    properties.insert("password".to_string(), Value::from(config.password.clone()));
+    if config.allow_insecure {
+        properties.insert("skip-cert-verify".to_string(), Value::from(true));
+    }
+    if let Some(sni) = &config.sni {
+        properties.insert("sni".to_string(), Value::from(sni.clone()));
+    }
+    if let Some(network) = &config.network {
+        properties.insert("network".to_string(), Value::from(network.clone()));
+    }
+
+    Proxy { name, properties }
+}
+
+/// Builds a Clash `type: vless` proxy from a parsed [`VlessConfig`].
+fn vless_to_proxy(config: &VlessConfig) -> Proxy {
+    use serde_yaml::Value;
+
+    let name = config
+        .remarks
+        .clone()
+        .unwrap_or_else(|| config.server.clone());
+
+    let mut properties: HashMap<String, Value> = HashMap::new();
+    properties.insert("type".to_string(), Value::from("vless"));
+    properties.insert("server".to_string(), Value::from(config.server.clone()));
+    properties.insert("port".to_string(), Value::from(config.server_port));
+    properties.insert("uuid".to_string(), Value::from(config.uuid.clone()));
+    if let Some(flow) = &config.flow {
+        properties.insert("flow".to_string(), Value::from(flow.clone()));
+    }
+    if let Some(network) = &config.network {
+        properties.insert("network".to_string(), Value::from(network.clone()));
+    }
+    if let Some(sni) = &config.sni {
+        properties.insert("servername".to_string(), Value::from(sni.clone()));
+    }
+    if config.insecure {
+        properties.insert("skip-cert-verify".to_string(), Value::from(true));
+    }
+
+    Proxy { name, properties }
+}
+
 /// Get protocol name as string
 ///
 /// # Arguments
@@ -106,6 +284,9 @@ pub fn parse_to_nodes(input: &str) -> Vec<Node> {
 pub fn protocol_to_string(protocol: &Protocol) -> &'static str {
     match protocol {
         Protocol::Vmess => "Vmess",
+        Protocol::Shadowsocks => "Shadowsocks",
+        Protocol::Trojan => "Trojan",
+        Protocol::Vless => "Vless",
         Protocol::Unidentified => "Unidentified",
     }
 }
@@ -135,6 +316,13 @@ mod tests {
         assert_eq!(result, Protocol::Unidentified);
     }
 
+    #[test]
+    fn test_detect_protocol_other_schemes() {
+        assert_eq!(detect_protocol("ss://YWVzLTI1Ni1nY20=@host:443"), Protocol::Shadowsocks);
+        assert_eq!(detect_protocol("trojan://pass@host:443"), Protocol::Trojan);
+        assert_eq!(detect_protocol("vless://uuid@host:443"), Protocol::Vless);
+    }
+
     #[test]
     fn test_parse_line_to_node() {
         let line = "vmess://eyJ2IjoiMiIsIn...}";
@@ -155,6 +343,9 @@ mod tests {
     #[test]
     fn test_protocol_to_string() {
         assert_eq!(protocol_to_string(&Protocol::Vmess), "Vmess");
+        assert_eq!(protocol_to_string(&Protocol::Shadowsocks), "Shadowsocks");
+        assert_eq!(protocol_to_string(&Protocol::Trojan), "Trojan");
+        assert_eq!(protocol_to_string(&Protocol::Vless), "Vless");
         assert_eq!(protocol_to_string(&Protocol::Unidentified), "Unidentified");
     }
 }
\ No newline at end of file
@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// A validated network host: an IPv4 literal, an IPv6 literal, or a DNS name.
+///
+/// Knowing which variant a node points at lets downstream code decide whether
+/// TLS knobs such as `sni`/`allowInsecure` are even meaningful (they are not for
+/// a bare IP literal).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Host {
+    V4(Ipv4Addr),
+    V6(Ipv6Addr),
+    Domain(String),
+}
+
+impl Host {
+    /// Classifies an already-split host component (brackets, if any, are stripped).
+    ///
+    /// Returns `None` when the string is empty or is neither a valid IP literal
+    /// nor a plausible DNS name.
+    pub fn parse(host: &str) -> Option<Host> {
+        let host = host.strip_prefix('[').and_then(|h| h.strip_suffix(']')).unwrap_or(host);
+        if host.is_empty() {
+            return None;
+        }
+
+        if let Ok(v4) = host.parse::<Ipv4Addr>() {
+            return Some(Host::V4(v4));
+        }
+        if let Ok(v6) = host.parse::<Ipv6Addr>() {
+            return Some(Host::V6(v6));
+        }
+        if is_dns_name(host) {
+            return Some(Host::Domain(host.to_string()));
+        }
+        None
+    }
+}
+
+impl std::fmt::Display for Host {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Host::V4(addr) => write!(f, "{}", addr),
+            // Re-bracket IPv6 literals so the result can go straight back into a URL.
+            Host::V6(addr) => write!(f, "[{}]", addr),
+            Host::Domain(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Splits a `host:port` (or `[ipv6]:port`) authority and validates both halves.
+///
+/// IPv6 literals must be bracketed; the closing `]` must be immediately followed
+/// by the `:` that introduces the port. Ports are rejected unless they fall in
+/// the `1..=65535` range.
+pub fn parse_host_port(input: &str) -> Option<(Host, u16)> {
+    let (host_part, port_part) = if let Some(rest) = input.strip_prefix('[') {
+        let close = rest.find(']')?;
+        let host = &rest[..close];
+        let after = &rest[close + 1..];
+        let port = after.strip_prefix(':')?;
+        (format!("[{}]", host), port)
+    } else {
+        let colon = input.rfind(':')?;
+        // More than one colon means an unbracketed IPv6 literal.
+        if input[..colon].contains(':') {
+            return None;
+        }
+        (input[..colon].to_string(), &input[colon + 1..])
+    };
+
+    let host = Host::parse(&host_part)?;
+    let port: u16 = port_part.parse().ok()?;
+    if port == 0 {
+        return None;
+    }
+
+    Some((host, port))
+}
+
+/// Loose DNS-name check: dot-separated labels of alphanumerics, `-` and `_`.
+fn is_dns_name(host: &str) -> bool {
+    !host.is_empty()
+        && host.split('.').all(|label| {
+            !label.is_empty()
+                && label
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_host_port_domain() {
+        let (host, port) = parse_host_port("example.com:443").unwrap();
+        assert_eq!(host, Host::Domain("example.com".to_string()));
+        assert_eq!(port, 443);
+    }
+
+    #[test]
+    fn test_parse_host_port_ipv6() {
+        let (host, port) = parse_host_port("[2001:db8::1]:443").unwrap();
+        assert_eq!(host, Host::V6("2001:db8::1".parse().unwrap()));
+        assert_eq!(port, 443);
+    }
+
+    #[test]
+    fn test_parse_host_port_ipv4() {
+        let (host, port) = parse_host_port("1.2.3.4:8388").unwrap();
+        assert_eq!(host, Host::V4("1.2.3.4".parse().unwrap()));
+        assert_eq!(port, 8388);
+    }
+
+    #[test]
+    fn test_reject_zero_port() {
+        assert!(parse_host_port("example.com:0").is_none());
+    }
+}
@@ -0,0 +1,152 @@
+use crate::nodes::host::Host;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Configuration for Hysteria2 protocol nodes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hysteria2Config {
+    pub server: String,
+    pub server_port: u16,
+    /// Classified host (IPv4/IPv6/domain) so callers know whether SNI applies.
+    pub host: Host,
+    pub password: String,  // authentication string
+    pub remarks: Option<String>,
+    pub insecure: bool,
+    pub sni: Option<String>,
+    pub obfs: Option<String>,
+    pub obfs_password: Option<String>,
+}
+
+/// Parsed query parameters from URL
+#[derive(Debug, Clone)]
+struct QueryParams {
+    insecure: bool,
+    sni: Option<String>,
+    obfs: Option<String>,
+    obfs_password: Option<String>,
+}
+
+/// Parses Hysteria2 configuration from a hysteria2:// URL
+/// Format: hysteria2://password@server:port?insecure=1&sni=domain&obfs=salamander&obfs-password=secret#remarks
+///
+/// Parsing is delegated to the `url` crate, matching [`crate::nodes::trojan`],
+/// so bracketed IPv6 literals and percent-encoded fields are handled uniformly.
+///
+/// # Arguments
+/// * `hysteria2_url` - The hysteria2:// URL to parse
+///
+/// # Returns
+/// * `Option<Hysteria2Config>` - Parsed configuration or None if parsing fails
+pub fn parse_hysteria2_config(hysteria2_url: &str) -> Option<Hysteria2Config> {
+    if !hysteria2_url.starts_with("hysteria2://") {
+        return None;
+    }
+
+    let url = Url::parse(hysteria2_url).ok()?;
+
+    let password = percent_decode(url.username());
+    if password.is_empty() {
+        return None;
+    }
+
+    let server = url.host_str()?.to_string();
+    let host = Host::parse(&server)?;
+    let server_port = url.port()?;
+    if server_port == 0 {
+        return None;
+    }
+
+    let params = parse_query_params(&url);
+
+    let remarks = url
+        .fragment()
+        .map(percent_decode)
+        .filter(|r| !r.is_empty());
+
+    Some(Hysteria2Config {
+        server,
+        server_port,
+        host,
+        password,
+        remarks,
+        insecure: params.insecure,
+        sni: params.sni,
+        obfs: params.obfs,
+        obfs_password: params.obfs_password,
+    })
+}
+
+/// Reads the recognised query parameters off a parsed hysteria2 URL.
+fn parse_query_params(url: &Url) -> QueryParams {
+    let mut params = QueryParams {
+        insecure: false,
+        sni: None,
+        obfs: None,
+        obfs_password: None,
+    };
+
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "insecure" => params.insecure = value == "1",
+            "sni" => params.sni = Some(value.into_owned()),
+            "obfs" => params.obfs = Some(value.into_owned()),
+            "obfs-password" => params.obfs_password = Some(value.into_owned()),
+            _ => {} // Ignore unknown parameters
+        }
+    }
+
+    params
+}
+
+/// Percent-decodes a component, falling back to the raw text on invalid UTF-8.
+fn percent_decode(value: &str) -> String {
+    urlencoding::decode(value)
+        .map(|cow| cow.into_owned())
+        .unwrap_or_else(|_| value.to_string())
+}
+
+impl Hysteria2Config {
+    /// Emits a canonical, spec-compliant `hysteria2://` URL.
+    ///
+    /// Query values and the remarks fragment are percent-encoded so that
+    /// `parse_hysteria2_config(&cfg.to_url())` round-trips back to `cfg`.
+    pub fn to_url(&self) -> String {
+        let mut url = format!(
+            "hysteria2://{}@{}:{}",
+            self.password, self.server, self.server_port
+        );
+
+        let mut params = Vec::new();
+        if self.insecure {
+            params.push("insecure=1".to_string());
+        }
+        if let Some(sni) = &self.sni {
+            params.push(format!("sni={}", urlencoding::encode(sni)));
+        }
+        if let Some(obfs) = &self.obfs {
+            params.push(format!("obfs={}", urlencoding::encode(obfs)));
+        }
+        if let Some(obfs_password) = &self.obfs_password {
+            params.push(format!("obfs-password={}", urlencoding::encode(obfs_password)));
+        }
+        if !params.is_empty() {
+            url.push('?');
+            url.push_str(&params.join("&"));
+        }
+
+        if let Some(remarks) = &self.remarks {
+            url.push('#');
+            url.push_str(&urlencoding::encode(remarks));
+        }
+
+        url
+    }
+}
+
+/// Validates if the configuration is complete and valid
+pub fn validate_hysteria2_config(config: &Hysteria2Config) -> bool {
+    !config.server.is_empty()
+        && config.server_port > 0
+        && config.server_port <= 65535
+        && !config.password.is_empty()
+}
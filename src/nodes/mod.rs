@@ -1,9 +1,160 @@
+pub mod host;
+pub mod hysteria2;
+pub mod proxy;
 pub mod vmess;
 pub mod ss;
-// pub mod trojan;
-// pub mod vless;
+pub mod trojan;
+pub mod vless;
 
-pub use vmess::{VmessConfig, parse_vmess_config};
-pub use ss::{ShadowsocksConfig, parse_ss_config, validate_ss_config};
-// pub use trojan::{TrojanConfig, parse_trojan_config, validate_trojan_config};
-// pub use vless::{VlessConfig, parse_vless_config, validate_vless_config};
\ No newline at end of file
+pub use host::{Host, parse_host_port};
+pub use hysteria2::{Hysteria2Config, parse_hysteria2_config, validate_hysteria2_config};
+pub use proxy::{ProxyConfig, parse_proxy_url};
+pub use vmess::{VmessConfig, parse_vmess_config, validate_vmess_config};
+pub use ss::{
+    ShadowsocksConfig, config_to_ss_url, parse_sip008, parse_ss_config, parse_ssr_config,
+    to_sip008, validate_ss_config,
+};
+pub use trojan::{TrojanConfig, parse_trojan_config, validate_trojan_config};
+pub use vless::{VlessConfig, parse_vless_config, to_vless_url, validate_vless_config};
+
+use std::error::Error;
+use std::fmt;
+
+/// Supported proxy protocols, keyed by their `scheme://` token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    Trojan,
+    Vmess,
+    Shadowsocks,
+    Vless,
+}
+
+/// Central scheme→protocol table. Adding a protocol is one row here plus its
+/// codec arm in [`parse_node`], rather than edits scattered across callers.
+const REGISTRY: &[(&str, Protocol)] = &[
+    ("trojan", Protocol::Trojan),
+    ("vmess", Protocol::Vmess),
+    ("ss", Protocol::Shadowsocks),
+    ("ssr", Protocol::Shadowsocks),
+    ("vless", Protocol::Vless),
+];
+
+impl Protocol {
+    /// Looks a scheme token (the part before `://`) up in the registry.
+    pub fn from_scheme(scheme: &str) -> Option<Protocol> {
+        REGISTRY
+            .iter()
+            .find(|(token, _)| *token == scheme)
+            .map(|(_, protocol)| *protocol)
+    }
+}
+
+/// A parsed node of any supported protocol.
+#[derive(Debug, Clone)]
+pub enum NodeConfig {
+    Trojan(TrojanConfig),
+    Vmess(VmessConfig),
+    Shadowsocks(ShadowsocksConfig),
+    Vless(VlessConfig),
+}
+
+impl NodeConfig {
+    /// The protocol variant this config belongs to.
+    pub fn protocol(&self) -> Protocol {
+        match self {
+            NodeConfig::Trojan(_) => Protocol::Trojan,
+            NodeConfig::Vmess(_) => Protocol::Vmess,
+            NodeConfig::Shadowsocks(_) => Protocol::Shadowsocks,
+            NodeConfig::Vless(_) => Protocol::Vless,
+        }
+    }
+}
+
+/// Errors surfaced by [`parse_node`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The `scheme://` prefix is missing or not in the registry.
+    UnknownScheme(String),
+    /// The scheme is known but its codec could not decode the payload.
+    MalformedPayload(Protocol),
+    /// A field required by the protocol is absent or unsupported.
+    UnsupportedField(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnknownScheme(scheme) => write!(f, "unknown scheme: {}", scheme),
+            ParseError::MalformedPayload(protocol) => {
+                write!(f, "malformed {:?} payload", protocol)
+            }
+            ParseError::UnsupportedField(field) => write!(f, "unsupported field: {}", field),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Parses any supported `scheme://…` node URL into a typed [`NodeConfig`].
+///
+/// Dispatch is driven by the scheme [`REGISTRY`], so callers no longer need to
+/// sniff the scheme themselves or fall back through per-protocol `Option`s.
+pub fn parse_node(url: &str) -> Result<NodeConfig, ParseError> {
+    let scheme = url
+        .split_once("://")
+        .map(|(scheme, _)| scheme)
+        .ok_or_else(|| ParseError::UnknownScheme(url.to_string()))?;
+
+    match Protocol::from_scheme(scheme) {
+        Some(Protocol::Trojan) => parse_trojan_config(url)
+            .map(NodeConfig::Trojan)
+            .ok_or(ParseError::MalformedPayload(Protocol::Trojan)),
+        Some(Protocol::Vmess) => parse_vmess_config(url)
+            .map(NodeConfig::Vmess)
+            .ok_or(ParseError::MalformedPayload(Protocol::Vmess)),
+        Some(Protocol::Shadowsocks) => {
+            let parsed = if url.starts_with("ssr://") {
+                ss::parse_ssr_config(url)
+            } else {
+                parse_ss_config(url)
+            };
+            parsed
+                .map(NodeConfig::Shadowsocks)
+                .ok_or(ParseError::MalformedPayload(Protocol::Shadowsocks))
+        }
+        Some(Protocol::Vless) => parse_vless_config(url)
+            .map(NodeConfig::Vless)
+            .ok_or(ParseError::MalformedPayload(Protocol::Vless)),
+        None => Err(ParseError::UnknownScheme(scheme.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_node_dispatches_by_scheme() {
+        let trojan = "trojan://f9ad69aa-bb58-48bb-93d7-47a8e93651d4@example.com:443";
+        match parse_node(trojan) {
+            Ok(NodeConfig::Trojan(cfg)) => assert_eq!(cfg.server, "example.com"),
+            other => panic!("expected trojan config, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_node_unknown_scheme() {
+        assert_eq!(
+            parse_node("gopher://example.com"),
+            Err(ParseError::UnknownScheme("gopher".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_node_malformed() {
+        assert_eq!(
+            parse_node("trojan://@:"),
+            Err(ParseError::MalformedPayload(Protocol::Trojan))
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use crate::nodes::hysteria2::{parse_hysteria2_config, Hysteria2Config};
+use crate::nodes::ss::{config_to_ss_url, parse_ss_config, ShadowsocksConfig};
+use crate::nodes::trojan::{parse_trojan_config, TrojanConfig};
+use crate::nodes::vless::{parse_vless_config, to_vless_url, VlessConfig};
+use crate::nodes::vmess::{parse_vmess_config, VmessConfig};
+
+/// A proxy node of any supported URL scheme, with a single parse/serialize
+/// entry point so a mixed subscription blob can be decoded and re-exported as
+/// one homogeneous set.
+#[derive(Debug, Clone)]
+pub enum ProxyConfig {
+    Vmess(VmessConfig),
+    Trojan(TrojanConfig),
+    Shadowsocks(ShadowsocksConfig),
+    Vless(VlessConfig),
+    Hysteria2(Hysteria2Config),
+}
+
+impl ProxyConfig {
+    /// Re-emits the config as a spec-compliant URL of its own scheme.
+    pub fn to_url(&self) -> String {
+        match self {
+            ProxyConfig::Vmess(cfg) => cfg.to_url(),
+            ProxyConfig::Trojan(cfg) => cfg.to_url(),
+            ProxyConfig::Shadowsocks(cfg) => config_to_ss_url(cfg),
+            ProxyConfig::Vless(cfg) => to_vless_url(cfg),
+            ProxyConfig::Hysteria2(cfg) => cfg.to_url(),
+        }
+    }
+}
+
+/// Parses any supported proxy URL, dispatching on its `scheme://` prefix.
+///
+/// `ss2022` links use the ordinary `ss://` scheme with a 2022 cipher, so they
+/// are handled by the Shadowsocks arm.
+pub fn parse_proxy_url(url: &str) -> Option<ProxyConfig> {
+    let scheme = url.split_once("://")?.0;
+    match scheme {
+        "vmess" => parse_vmess_config(url).map(ProxyConfig::Vmess),
+        "trojan" => parse_trojan_config(url).map(ProxyConfig::Trojan),
+        "ss" => parse_ss_config(url).map(ProxyConfig::Shadowsocks),
+        "vless" => parse_vless_config(url).map(ProxyConfig::Vless),
+        "hysteria2" => parse_hysteria2_config(url).map(ProxyConfig::Hysteria2),
+        _ => None,
+    }
+}
@@ -17,19 +17,6 @@ pub struct ShadowsocksConfig {
     pub obfs_param: Option<String>,
 }
 
-/// User info extracted from base64 encoded part
-#[derive(Debug, Clone)]
-struct UserInfo {
-    pub method: String,
-    pub password: String,
-    pub server: String,
-    pub port: u16,
-    pub remarks: Option<String>,
-    pub protocol: Option<String>,
-    pub obfs: Option<String>,
-    pub obfs_param: Option<String>,
-}
-
 /// Parses Shadowsocks configuration from an ss:// URL
 /// Supports both SIP002 (base64 encoded) and legacy formats
 ///
@@ -46,149 +33,131 @@ pub fn parse_ss_config(ss_url: &str) -> Option<ShadowsocksConfig> {
     // Remove ss:// prefix
     let url_part = &ss_url[5..];
 
-    // Check if it's SIP002 (base64 encoded) or legacy format
+    // SIP002 links keep the authority unencoded, so the `@` is visible; the
+    // older whole-blob form base64-encodes everything after `ss://`.
     if url_part.contains('@') {
-        // Legacy format: ss://method:password@server:port#remarks
-        parse_legacy_ss_format(url_part)
-    } else {
-        // SIP002 format: ss://base64 userinfo@server:port#remarks
         parse_sip002_ss_format(url_part)
+    } else {
+        parse_legacy_ss_format(url_part)
     }
 }
 
-/// Parses SIP002 format with base64 encoded userinfo
-/// Format: ss://base64(method:password:server:port)@server:port#remarks
+/// Parses a SIP002 URL: `ss://base64url(method:password)@host:port/?plugin=…#tag`.
+///
+/// The userinfo is decoded with the URL-safe, no-pad engine SIP002 mandates
+/// (falling back to the standard alphabet and to plain text), the authority is
+/// read unencoded, and the `?plugin=` query is split into the `plugin` name and
+/// its `plugin_opts`.
 fn parse_sip002_ss_format(url_part: &str) -> Option<ShadowsocksConfig> {
-    // Find the @ separator
     let at_pos = url_part.find('@')?;
-    let base64_part = &url_part[..at_pos];
-    let server_part = &url_part[at_pos + 1..];
-
-    // Decode base64 userinfo
-    let userinfo_bytes = general_purpose::STANDARD.decode(base64_part).ok()?;
-    let userinfo_str = String::from_utf8(userinfo_bytes).ok()?;
-
-    // Parse userinfo - supports both format with/without protocol and obfs
-    let userinfo = parse_userinfo(&userinfo_str)?;
+    let userinfo_part = &url_part[..at_pos];
+    let authority = &url_part[at_pos + 1..];
+
+    // method:password, with graceful fallback between base64 flavours.
+    let userinfo_str = decode_userinfo(userinfo_part);
+    let (method, password) = userinfo_str.split_once(':')?;
+
+    // Split off the fragment (remarks) and the query (plugin) from the authority.
+    let (before_fragment, remarks) = match authority.split_once('#') {
+        Some((head, frag)) => (
+            head,
+            Some(
+                urlencoding::decode(frag)
+                    .map(|c| c.into_owned())
+                    .unwrap_or_else(|_| frag.to_string()),
+            ),
+        ),
+        None => (authority, None),
+    };
+    let (host_port, query) = match before_fragment.split_once('?') {
+        Some((head, query)) => (head, Some(query)),
+        None => (before_fragment, None),
+    };
 
-    // Parse server:port part (may include #remarks)
-    let (server_port, remarks) = parse_server_port_remarks(server_part)?;
+    let (server_port, _) = parse_server_port_remarks(host_port.trim_end_matches('/'))?;
+    let (plugin, plugin_opts) = query.map(parse_plugin_query).unwrap_or((None, None));
 
     Some(ShadowsocksConfig {
         server: server_port.server,
         server_port: server_port.port,
-        method: userinfo.method,
-        password: userinfo.password,
+        method: method.to_string(),
+        password: password.to_string(),
         remarks,
-        plugin: None,
-        plugin_opts: None,
-        protocol: userinfo.protocol,
-        obfs: userinfo.obfs,
-        obfs_param: userinfo.obfs_param,
+        plugin,
+        plugin_opts,
+        protocol: None,
+        obfs: None,
+        obfs_param: None,
     })
 }
 
-/// Parses legacy Shadowsocks format
-/// Format: ss://method:password@server:port#remarks
-fn parse_legacy_ss_format(url_part: &str) -> Option<ShadowsocksConfig> {
-    // Find the last : before the server part
-    let parts: Vec<&str> = url_part.rsplitn(2, ':').collect();
-    if parts.len() != 2 {
-        return None;
+/// Decodes the userinfo blob, preferring URL-safe/no-pad then standard base64,
+/// and finally treating it as already-plain `method:password` text.
+fn decode_userinfo(userinfo: &str) -> String {
+    for engine in [general_purpose::URL_SAFE_NO_PAD, general_purpose::STANDARD] {
+        if let Ok(bytes) = engine.decode(userinfo) {
+            if let Ok(text) = String::from_utf8(bytes) {
+                if text.contains(':') {
+                    return text;
+                }
+            }
+        }
     }
+    userinfo.to_string()
+}
 
-    let userpass_part = parts[1];
-    let server_port_remarks = parts[0];
-
-    // Parse method:password
-    let userpass_parts: Vec<&str> = userpass_part.split(':').collect();
-    if userpass_parts.len() != 2 {
-        return None;
+/// Splits a `plugin=name;opt=val;…` query value into `(plugin, plugin_opts)`.
+fn parse_plugin_query(query: &str) -> (Option<String>, Option<String>) {
+    for pair in query.split('&') {
+        if let Some(value) = pair.strip_prefix("plugin=") {
+            let decoded = urlencoding::decode(value)
+                .map(|c| c.into_owned())
+                .unwrap_or_else(|_| value.to_string());
+            return match decoded.split_once(';') {
+                Some((name, opts)) => (Some(name.to_string()), Some(opts.to_string())),
+                None => (Some(decoded), None),
+            };
+        }
     }
+    (None, None)
+}
 
-    // Parse server:port and remarks
-    let (server_port, remarks) = parse_server_port_remarks(server_port_remarks)?;
+/// Parses the legacy whole-blob format: the text after `ss://` is base64 of
+/// `method:password@host:port` (optionally followed by `#tag`).
+fn parse_legacy_ss_format(url_part: &str) -> Option<ShadowsocksConfig> {
+    let (encoded, fragment) = match url_part.split_once('#') {
+        Some((head, frag)) => (head, Some(frag)),
+        None => (url_part, None),
+    };
 
-    Some(ShadowsocksConfig {
-        server: server_port.server,
-        server_port: server_port.port,
-        method: userpass_parts[0].to_string(),
-        password: userpass_parts[1].to_string(),
-        remarks,
-        plugin: None,
-        plugin_opts: None,
-        protocol: None,
-        obfs: None,
-        obfs_param: None,
-    })
+    let decoded = decode_userinfo(encoded);
+    let rebuilt = match fragment {
+        Some(frag) => format!("{}#{}", decoded, frag),
+        None => decoded,
+    };
+    parse_sip002_ss_format(&rebuilt)
 }
 
-/// Parses user info from decoded string
-/// Supports format: method:password:server:port or method:password:protocol:obfs:obfsparam@server:port
-fn parse_userinfo(userinfo_str: &str) -> Option<UserInfo> {
-    let parts: Vec<&str> = userinfo_str.split(':').collect();
+/// Re-emits a spec-correct SIP002 URL for a parsed config.
+pub fn config_to_ss_url(config: &ShadowsocksConfig) -> String {
+    let userinfo = general_purpose::URL_SAFE_NO_PAD
+        .encode(format!("{}:{}", config.method, config.password));
+    let mut url = format!("ss://{}@{}:{}", userinfo, config.server, config.server_port);
 
-    if parts.len() < 2 {
-        return None;
+    if let Some(plugin) = &config.plugin {
+        let value = match &config.plugin_opts {
+            Some(opts) => format!("{};{}", plugin, opts),
+            None => plugin.clone(),
+        };
+        url.push_str(&format!("/?plugin={}", urlencoding::encode(&value)));
     }
 
-    // Always have method and password
-    let method = parts[0].to_string();
-    let password = parts[1].to_string();
-
-    // Extract server, port, and optional fields
-    if parts.len() >= 4 {
-        // Format: method:password:server:port or with protocol/obfs
-        if parts.len() == 4 {
-            // method:password:server:port
-            Some(UserInfo {
-                method,
-                password,
-                server: parts[2].to_string(),
-                port: parts[3].parse().ok()?,
-                remarks: None,
-                protocol: None,
-                obfs: None,
-                obfs_param: None,
-            })
-        } else if parts.len() == 5 {
-            // method:password:protocol:obfs:server:port
-            Some(UserInfo {
-                method,
-                password,
-                server: parts[4].to_string(),
-                port: parts[5].parse().ok()?,
-                remarks: None,
-                protocol: Some(parts[2].to_string()),
-                obfs: Some(parts[3].to_string()),
-                obfs_param: None,
-            })
-        } else {
-            // More complex format with obfs params
-            Some(UserInfo {
-                method,
-                password,
-                server: parts[parts.len() - 2].to_string(),
-                port: parts[parts.len() - 1].parse().ok()?,
-                remarks: None,
-                protocol: if parts.len() > 2 { Some(parts[2].to_string()) } else { None },
-                obfs: if parts.len() > 3 { Some(parts[3].to_string()) } else { None },
-                obfs_param: if parts.len() > 4 { Some(parts[4].to_string()) } else { None },
-            })
-        }
-    } else {
-        // Simple format: method:password
-        Some(UserInfo {
-            method,
-            password,
-            server: "".to_string(),
-            port: 0,
-            remarks: None,
-            protocol: None,
-            obfs: None,
-            obfs_param: None,
-        })
+    if let Some(remarks) = &config.remarks {
+        url.push('#');
+        url.push_str(&urlencoding::encode(remarks));
     }
+
+    url
 }
 
 /// Helper struct to hold server, port, and remarks
@@ -246,6 +215,116 @@ fn parse_encoded_port(port_str: &str) -> Option<u16> {
     }
 }
 
+/// Parses a ShadowsocksR `ssr://` URL.
+///
+/// The payload after `ssr://` is base64url of
+/// `server:port:protocol:method:obfs:base64pass/?obfsparam=…&protoparam=…&remarks=…`,
+/// where the password and every query parameter are themselves base64url-encoded.
+pub fn parse_ssr_config(ssr_url: &str) -> Option<ShadowsocksConfig> {
+    let payload = ssr_url.strip_prefix("ssr://")?;
+    let decoded = decode_base64url(payload)?;
+
+    let (main_part, query) = match decoded.split_once("/?") {
+        Some((main, query)) => (main, Some(query)),
+        None => (decoded.as_str(), None),
+    };
+
+    // server:port:protocol:method:obfs:base64pass
+    let fields: Vec<&str> = main_part.split(':').collect();
+    if fields.len() != 6 {
+        return None;
+    }
+    let server = fields[0].to_string();
+    let server_port = fields[1].parse().ok()?;
+    let protocol = fields[2].to_string();
+    let method = fields[3].to_string();
+    let obfs = fields[4].to_string();
+    let password = decode_base64url(fields[5])?;
+
+    let mut obfs_param = None;
+    let mut remarks = None;
+    if let Some(query) = query {
+        for pair in query.split('&') {
+            if let Some((key, value)) = pair.split_once('=') {
+                match key {
+                    "obfsparam" => obfs_param = decode_base64url(value),
+                    "remarks" => remarks = decode_base64url(value),
+                    _ => {} // protoparam/group are not carried on the config
+                }
+            }
+        }
+    }
+
+    Some(ShadowsocksConfig {
+        server,
+        server_port,
+        method,
+        password,
+        remarks,
+        plugin: None,
+        plugin_opts: None,
+        protocol: Some(protocol),
+        obfs: Some(obfs),
+        obfs_param,
+    })
+}
+
+/// Decodes a base64url (no-pad, falling back to standard) blob to UTF-8 text.
+fn decode_base64url(value: &str) -> Option<String> {
+    for engine in [general_purpose::URL_SAFE_NO_PAD, general_purpose::STANDARD] {
+        if let Ok(bytes) = engine.decode(value) {
+            if let Ok(text) = String::from_utf8(bytes) {
+                return Some(text);
+            }
+        }
+    }
+    None
+}
+
+/// SIP008 online-config document: `{"version":1,"servers":[…]}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Sip008Document {
+    version: u32,
+    servers: Vec<ShadowsocksConfig>,
+}
+
+/// Parses a SIP008 JSON document into a list of [`ShadowsocksConfig`].
+///
+/// The SIP008 server object (`server`, `server_port`, `password`, `method`,
+/// `plugin`, `plugin_opts`, `remarks`) lines up with the struct fields, so the
+/// servers deserialize directly; unknown keys are ignored.
+pub fn parse_sip008(json: &str) -> Option<Vec<ShadowsocksConfig>> {
+    serde_json::from_str::<Sip008Document>(json)
+        .ok()
+        .map(|doc| doc.servers)
+}
+
+/// Emits a SIP008 (version 1) JSON document for the given configs.
+pub fn to_sip008(configs: &[ShadowsocksConfig]) -> String {
+    let servers: Vec<serde_json::Value> = configs
+        .iter()
+        .map(|config| {
+            let mut server = serde_json::Map::new();
+            server.insert("server".into(), serde_json::json!(config.server));
+            server.insert("server_port".into(), serde_json::json!(config.server_port));
+            server.insert("method".into(), serde_json::json!(config.method));
+            server.insert("password".into(), serde_json::json!(config.password));
+            if let Some(remarks) = &config.remarks {
+                server.insert("remarks".into(), serde_json::json!(remarks));
+            }
+            if let Some(plugin) = &config.plugin {
+                server.insert("plugin".into(), serde_json::json!(plugin));
+            }
+            if let Some(plugin_opts) = &config.plugin_opts {
+                server.insert("plugin_opts".into(), serde_json::json!(plugin_opts));
+            }
+            serde_json::Value::Object(server)
+        })
+        .collect();
+
+    serde_json::json!({ "version": 1, "servers": servers }).to_string()
+}
+
 /// Validates if the configuration is complete and valid
 pub fn validate_ss_config(config: &ShadowsocksConfig) -> bool {
     !config.server.is_empty()
@@ -279,7 +358,29 @@ mod tests {
         assert_eq!(config.server, "hk11.cxk.lol");
         assert_eq!(config.server_port, 25451);
         assert_eq!(config.method, "aes-128-gcm");
-        assert_eq!(config.password, "bUqO4MDEtYWE2YS00YTIwLWI2OTAtNGUzNzdkY2ZjOTJl");
+        assert_eq!(config.password, "943b2801-aa6a-4a20-b690-4e377dcfc92e");
+    }
+
+    #[test]
+    fn test_parse_sip002_with_plugin() {
+        let ss_url = "ss://YWVzLTI1Ni1nY206cGFzcw@example.com:8388/?plugin=obfs-local%3Bobfs%3Dtls#MyServer";
+        let config = parse_ss_config(ss_url).unwrap();
+        assert_eq!(config.method, "aes-256-gcm");
+        assert_eq!(config.password, "pass");
+        assert_eq!(config.plugin, Some("obfs-local".to_string()));
+        assert_eq!(config.plugin_opts, Some("obfs=tls".to_string()));
+        assert_eq!(config.remarks, Some("MyServer".to_string()));
+    }
+
+    #[test]
+    fn test_ss_url_round_trip() {
+        let ss_url = "ss://YWVzLTI1Ni1nY206cGFzcw@example.com:8388#MyServer";
+        let config = parse_ss_config(ss_url).unwrap();
+        let reparsed = parse_ss_config(&config_to_ss_url(&config)).unwrap();
+        assert_eq!(config.server, reparsed.server);
+        assert_eq!(config.method, reparsed.method);
+        assert_eq!(config.password, reparsed.password);
+        assert_eq!(config.remarks, reparsed.remarks);
     }
 
     #[test]
@@ -303,6 +404,39 @@ mod tests {
         assert_eq!(config.remarks, Some("MyServer".to_string()));
     }
 
+    #[test]
+    fn test_parse_ssr_config() {
+        let pass = general_purpose::URL_SAFE_NO_PAD.encode("pass");
+        let remarks = general_purpose::URL_SAFE_NO_PAD.encode("node-a");
+        let body = format!(
+            "example.com:8388:origin:aes-256-cfb:plain:{}/?remarks={}",
+            pass, remarks
+        );
+        let ssr_url = format!("ssr://{}", general_purpose::URL_SAFE_NO_PAD.encode(body));
+
+        let config = parse_ssr_config(&ssr_url).unwrap();
+        assert_eq!(config.server, "example.com");
+        assert_eq!(config.server_port, 8388);
+        assert_eq!(config.protocol, Some("origin".to_string()));
+        assert_eq!(config.method, "aes-256-cfb");
+        assert_eq!(config.obfs, Some("plain".to_string()));
+        assert_eq!(config.password, "pass");
+        assert_eq!(config.remarks, Some("node-a".to_string()));
+    }
+
+    #[test]
+    fn test_sip008_round_trip() {
+        let json = r#"{"version":1,"servers":[{"server":"example.com","server_port":8388,"method":"aes-256-gcm","password":"pass","remarks":"node-a"}]}"#;
+        let configs = parse_sip008(json).unwrap();
+        assert_eq!(configs.len(), 1);
+        assert_eq!(configs[0].server, "example.com");
+        assert_eq!(configs[0].remarks, Some("node-a".to_string()));
+
+        let reparsed = parse_sip008(&to_sip008(&configs)).unwrap();
+        assert_eq!(reparsed[0].server_port, 8388);
+        assert_eq!(reparsed[0].method, "aes-256-gcm");
+    }
+
     #[test]
     fn test_validate_config() {
         let config = ShadowsocksConfig {
@@ -1,11 +1,14 @@
+use crate::nodes::host::Host;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use url::Url;
 
 /// Configuration for Trojan protocol nodes
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TrojanConfig {
     pub server: String,
     pub server_port: u16,
+    /// Classified host (IPv4/IPv6/domain) so callers know whether SNI applies.
+    pub host: Host,
     pub password: String,  // UUID-based password
     pub remarks: Option<String>,
     pub allow_insecure: bool,
@@ -20,10 +23,15 @@ pub struct QueryParams {
     pub allow_insecure: bool,
     pub peer: Option<String>,
     pub sni: Option<String>,
+    pub network: Option<String>,
 }
 
 /// Parses Trojan configuration from a trojan:// URL
-/// Format: trojan://uuid@server:port?allowInsecure=1&peer=host&sni=domain#remarks
+/// Format: trojan://password@server:port?allowInsecure=1&peer=host&sni=domain#remarks
+///
+/// Parsing is delegated to an RFC 3986 URL parser (the `url` crate), so bracketed
+/// IPv6 literals, percent-encoded passwords/remarks, and repeated `?`/`#` characters
+/// are handled correctly instead of by ad-hoc string slicing.
 ///
 /// # Arguments
 /// * `trojan_url` - The trojan:// URL to parse
@@ -35,27 +43,36 @@ pub fn parse_trojan_config(trojan_url: &str) -> Option<TrojanConfig> {
         return None;
     }
 
-    // Remove trojan:// prefix
-    let url_part = &trojan_url[8..];
+    let url = Url::parse(trojan_url).ok()?;
 
-    // Find the @ separator between password and server
-    let at_pos = url_part.find('@')?;
-    let password_part = &url_part[..at_pos];
-    let server_part = &url_part[at_pos + 1..];
+    // The userinfo carries the password; `username()` returns it still
+    // percent-encoded, so it is decoded here.
+    let password = percent_decode(url.username());
+    if password.is_empty() {
+        return None;
+    }
 
-    // Extract UUID password
-    let password = extract_uuid(password_part)?;
+    // `host_str()` keeps the brackets around IPv6 literals; `Host::parse`
+    // strips and classifies them, and the port range is checked here rather
+    // than being deferred to `validate_trojan_config`.
+    let server = url.host_str()?.to_string();
+    let host = Host::parse(&server)?;
+    let server_port = url.port()?;
+    if server_port == 0 {
+        return None;
+    }
 
-    // Parse server:port and query parameters
-    let (server_port_query, remarks) = parse_server_query_remarks(server_part)?;
-    let (server_port, query) = parse_server_port_query(server_port_query)?;
+    let params = parse_query_params(&url);
 
-    // Parse query parameters
-    let params = parse_query_params(query);
+    let remarks = url
+        .fragment()
+        .map(percent_decode)
+        .filter(|r| !r.is_empty());
 
     Some(TrojanConfig {
-        server: server_port.server,
-        server_port: server_port.port,
+        server,
+        server_port,
+        host,
         password,
         remarks,
         allow_insecure: params.allow_insecure,
@@ -65,105 +82,8 @@ pub fn parse_trojan_config(trojan_url: &str) -> Option<TrojanConfig> {
     })
 }
 
-/// Extracts UUID from password part
-/// Validates UUID format with optional hyphens
-fn extract_uuid(password_part: &str) -> Option<String> {
-    // Remove any leading/trailing whitespace
-    let cleaned = password_part.trim();
-
-    // Basic UUID format validation (simplified)
-    if cleaned.len() != 32 && cleaned.len() != 36 {
-        return None;
-    }
-
-    // Check if it looks like a UUID (hex characters with optional hyphens)
-    if cleaned.chars().all(|c| c.is_ascii_hexdigit() || c == '-') {
-        Some(cleaned.to_string())
-    } else {
-        None
-    }
-}
-
-/// Helper struct to hold server, port, and query
-#[derive(Debug, Clone)]
-struct ServerPortQuery {
-    server: String,
-    port: u16,
-    query: String,
-}
-
-/// Parses server:port?query#remarks format
-fn parse_server_query_remarks(server_query_remarks: &str) -> Option<(ServerPortQuery, Option<String>)> {
-    // Split on ? to separate query
-    let mut parts: Vec<&str> = server_query_remarks.split('?').collect();
-    if parts.is_empty() {
-        return None;
-    }
-
-    let server_port_part = parts[0];
-    let query = if parts.len() > 1 { parts[1].to_string() } else { String::new() };
-
-    // Check if there are #remarks
-    let (server_port_with_query, remarks) = if let Some(hash_pos) = server_port_part.find('#') {
-        let remarks_part = &server_port_part[hash_pos + 1..];
-        let server_port_part = &server_port_part[..hash_pos];
-        let decoded_remarks = urlencoding::decode(remarks_part).ok_or_else(|_| remarks_part.to_string())?;
-        (server_port_part.to_string(), Some(decoded_remarks))
-    } else {
-        (server_port_part.to_string(), None)
-    };
-
-    parse_server_port_query_with_query(&server_port_with_query, &query, remarks)
-}
-
-/// Parses server:port with query part
-fn parse_server_port_query_with_query(server_port_query: &str, additional_query: &str, remarks: Option<String>) -> Option<(ServerPortQuery, Option<String>)> {
-    // Combine queries
-    let full_query = if additional_query.is_empty() {
-        server_port_query.to_string()
-    } else {
-        format!("{}&{}", server_port_query, additional_query)
-    };
-
-    // Split on ? to get server:port part
-    let parts: Vec<&str> = full_query.split('?').collect();
-    if parts.is_empty() {
-        return None;
-    }
-
-    let server_port_part = parts[0];
-    let query = if parts.len() > 1 { parts[1].to_string() } else { String::new() };
-
-    // Parse server:port (handle IPv6 addresses)
-    if let Some(last_colon) = server_port_part.rfind(':') {
-        let server = server_port_part[..last_colon].to_string();
-        let port_str = &server_port_part[last_colon + 1..];
-
-        let port = port_str.parse().ok()?;
-
-        Some((ServerPortQuery { server, port }, remarks))
-    } else {
-        None
-    }
-}
-
-/// Parses server:port format without query
-fn parse_server_port_query(server_port_part: &str) -> Option<(ServerPortQuery, String)> {
-    // Parse server:port (handle IPv6 addresses)
-    if let Some(last_colon) = server_port_part.rfind(':') {
-        let server = server_port_part[..last_colon].to_string();
-        let port_str = &server_port_part[last_colon + 1..];
-
-        let port = port_str.parse().ok()?;
-
-        Some((ServerPortQuery { server, port }, String::new()))
-    } else {
-        None
-    }
-}
-
-/// Parses query parameters into a structured format
-fn parse_query_params(query: &str) -> QueryParams {
+/// Reads the recognised query parameters off a parsed trojan URL.
+fn parse_query_params(url: &Url) -> QueryParams {
     let mut params = QueryParams {
         allow_insecure: false,
         peer: None,
@@ -171,35 +91,61 @@ fn parse_query_params(query: &str) -> QueryParams {
         network: None,
     };
 
-    if query.is_empty() {
-        return params;
-    }
-
-    // Parse individual parameters
-    for param in query.split('&') {
-        let mut key_value = param.splitn(2, '=');
-        if let (Some(key), value) = (key_value.next(), key_value.next()) {
-            match key {
-                "allowInsecure" => {
-                    params.allow_insecure = value == "1";
-                }
-                "peer" => {
-                    params.peer = Some(value.to_string());
-                }
-                "sni" => {
-                    params.sni = Some(urlencoding::decode(value).ok_or_else(|_| value.to_string())?);
-                }
-                "network" => {
-                    params.network = Some(value.to_string());
-                }
-                _ => {} // Ignore unknown parameters
-            }
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "allowInsecure" => params.allow_insecure = value == "1",
+            "peer" => params.peer = Some(value.into_owned()),
+            "sni" => params.sni = Some(value.into_owned()),
+            "network" | "type" => params.network = Some(value.into_owned()),
+            _ => {} // Ignore unknown parameters
         }
     }
 
     params
 }
 
+/// Percent-decodes a component, falling back to the raw text on invalid UTF-8.
+fn percent_decode(value: &str) -> String {
+    urlencoding::decode(value)
+        .map(|cow| cow.into_owned())
+        .unwrap_or_else(|_| value.to_string())
+}
+
+impl TrojanConfig {
+    /// Emits a canonical, spec-compliant `trojan://` URL.
+    ///
+    /// Query values and the remarks fragment are percent-encoded so that
+    /// `parse_trojan_config(&cfg.to_url())` round-trips back to `cfg`.
+    pub fn to_url(&self) -> String {
+        let mut url = format!("trojan://{}@{}:{}", self.password, self.server, self.server_port);
+
+        let mut params = Vec::new();
+        if self.allow_insecure {
+            params.push("allowInsecure=1".to_string());
+        }
+        if let Some(peer) = &self.peer {
+            params.push(format!("peer={}", urlencoding::encode(peer)));
+        }
+        if let Some(sni) = &self.sni {
+            params.push(format!("sni={}", urlencoding::encode(sni)));
+        }
+        if let Some(network) = &self.network {
+            params.push(format!("type={}", urlencoding::encode(network)));
+        }
+        if !params.is_empty() {
+            url.push('?');
+            url.push_str(&params.join("&"));
+        }
+
+        if let Some(remarks) = &self.remarks {
+            url.push('#');
+            url.push_str(&urlencoding::encode(remarks));
+        }
+
+        url
+    }
+}
+
 /// Validates if the configuration is complete and valid
 pub fn validate_trojan_config(config: &TrojanConfig) -> bool {
     !config.server.is_empty()
@@ -249,35 +195,49 @@ mod tests {
         assert_eq!(config.allow_insecure, true);
         assert_eq!(config.peer, Some("cdn.alibaba.com".to_string()));
         assert_eq!(config.sni, Some("cdn.alibaba.com".to_string()));
-        assert_eq!(config.remarks, Some("☀☀☀☀☀☀ 北京-上海 2001".to_string()));
+        assert_eq!(config.remarks, Some("🇦🇺 澳大利亚 01".to_string()));
     }
 
     #[test]
-    fn test_extract_uuid() {
-        let uuid = "f9ad69aa-bb58-48bb-93d7-47a8e93651d4";
-        let extracted = extract_uuid(uuid);
-        assert_eq!(extracted, Some(uuid.to_string()));
-
-        let invalid_uuid = "not-a-uuid";
-        let extracted_invalid = extract_uuid(invalid_uuid);
-        assert_eq!(extracted_invalid, None);
+    fn test_parse_ipv6_host() {
+        let trojan_url = "trojan://f9ad69aa-bb58-48bb-93d7-47a8e93651d4@[2001:db8::1]:443";
+        let config = parse_trojan_config(trojan_url).unwrap();
+        assert_eq!(config.server, "[2001:db8::1]");
+        assert_eq!(config.server_port, 443);
     }
 
     #[test]
     fn test_parse_query_params() {
-        let query = "allowInsecure=1&peer=cdn.alibaba.com&sni=cdn.alibaba.com";
-        let params = parse_query_params(query);
+        let url = Url::parse("trojan://pw@host:443?allowInsecure=1&peer=cdn.alibaba.com&sni=cdn.alibaba.com").unwrap();
+        let params = parse_query_params(&url);
 
         assert_eq!(params.allow_insecure, true);
         assert_eq!(params.peer, Some("cdn.alibaba.com".to_string()));
         assert_eq!(params.sni, Some("cdn.alibaba.com".to_string()));
     }
 
+    #[test]
+    fn test_to_url_round_trip() {
+        use crate::nodes::{NodeConfig, parse_node};
+
+        let url = "trojan://f9ad69aa-bb58-48bb-93d7-47a8e93651d4@example.com:443?allowInsecure=1&peer=cdn.alibaba.com&sni=cdn.alibaba.com&type=ws#%F0%9F%87%A6%F0%9F%87%BA%20%E6%BE%B3%E5%A4%A7%E5%88%A9%E4%BA%9A%2001";
+        let config = match parse_node(url).unwrap() {
+            NodeConfig::Trojan(cfg) => cfg,
+            other => panic!("expected trojan config, got {:?}", other),
+        };
+        let reparsed = match parse_node(&config.to_url()).unwrap() {
+            NodeConfig::Trojan(cfg) => cfg,
+            other => panic!("expected trojan config, got {:?}", other),
+        };
+        assert_eq!(config, reparsed);
+    }
+
     #[test]
     fn test_validate_trojan_config() {
         let config = TrojanConfig {
             server: "example.com".to_string(),
             server_port: 443,
+            host: Host::Domain("example.com".to_string()),
             password: "f9ad69aa-bb58-48bb-93d7-47a8e93651d4".to_string(),
             remarks: None,
             allow_insecure: false,
@@ -288,4 +248,4 @@ mod tests {
 
         assert!(validate_trojan_config(&config));
     }
-}
\ No newline at end of file
+}
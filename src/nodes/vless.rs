@@ -104,7 +104,7 @@ pub fn parse_vless_config(vless_url: &str) -> Option<VlessConfig> {
         uuid,
         encryption: params.encryption,
         transport: build_transport_config(&params),
-        network: params.network,
+        network: params.r#type.clone(),
         tls: build_tls_config(&params),
         flow: params.flow,
         packet_encoding: params.packet_encoding,
@@ -161,8 +161,7 @@ fn parse_server_query_remarks(server_query_remarks: &str) -> Option<(ServerPortQ
     let (server_port_with_query, remarks) = if let Some(hash_pos) = server_port_part.find('#') {
         let remarks_part = &server_port_part[hash_pos + 1..];
         let server_port_part = &server_port_part[..hash_pos];
-        let decoded_remarks = urlencoding::decode(remarks_part).ok_or_else(|_| remarks_part.to_string())?;
-        (server_port_part.to_string(), Some(decoded_remarks))
+        (server_port_part.to_string(), Some(percent_decode(remarks_part)))
     } else {
         (server_port_part.to_string(), None)
     };
@@ -243,23 +242,24 @@ fn parse_query_params(query: &str) -> QueryParams {
     // Parse individual parameters
     for param in query.split('&') {
         let mut key_value = param.splitn(2, '=');
-        if let (Some(key), value) = (key_value.next(), key_value.next()) {
+        if let (Some(key), Some(raw_value)) = (key_value.next(), key_value.next()) {
+            let value = percent_decode(raw_value);
             match key {
-                "type" => params.r#type = Some(value.to_string()),
-                "encryption" => params.encryption = Some(value.to_string()),
-                "host" => params.host = Some(value.to_string()),
-                "path" => params.path = Some(value.to_string()),
-                "headerType" => params.header_type = Some(value.to_string()),
-                "quicSecurity" => params.quic_security = Some(value.to_string()),
-                "serviceName" => params.service_name = Some(value.to_string()),
-                "security" => params.security = Some(value.to_string()),
-                "flow" => params.flow = Some(value.to_string()),
-                "packetEncoding" => params.packet_encoding = Some(value.to_string()),
-                "fp" => params.fp = Some(value.to_string()),
-                "sid" => params.sid = Some(value.to_string()),
-                "pbk" => params.pbk = Some(value.to_string()),
-                "insecure" => params.insecure = Some(value.to_string()),
-                "sni" => params.sni = Some(value.to_string()),
+                "type" => params.r#type = Some(value),
+                "encryption" => params.encryption = Some(value),
+                "host" => params.host = Some(value),
+                "path" => params.path = Some(value),
+                "headerType" => params.header_type = Some(value),
+                "quicSecurity" => params.quic_security = Some(value),
+                "serviceName" => params.service_name = Some(value),
+                "security" => params.security = Some(value),
+                "flow" => params.flow = Some(value),
+                "packetEncoding" => params.packet_encoding = Some(value),
+                "fp" => params.fp = Some(value),
+                "sid" => params.sid = Some(value),
+                "pbk" => params.pbk = Some(value),
+                "insecure" => params.insecure = Some(value),
+                "sni" => params.sni = Some(value),
                 _ => {} // Ignore unknown parameters
             }
         }
@@ -268,6 +268,13 @@ fn parse_query_params(query: &str) -> QueryParams {
     params
 }
 
+/// Percent-decodes a component, falling back to the raw text on invalid UTF-8.
+fn percent_decode(value: &str) -> String {
+    urlencoding::decode(value)
+        .map(|cow| cow.into_owned())
+        .unwrap_or_else(|_| value.to_string())
+}
+
 /// Builds transport configuration from query parameters
 fn build_transport_config(params: &QueryParams) -> Option<TransportConfig> {
     if params.r#type.is_none() && params.host.is_none() && params.path.is_none() {
@@ -328,6 +335,75 @@ pub fn config_to_string(config: &VlessConfig) -> String {
     )
 }
 
+/// Reconstructs a spec-compliant `vless://uuid@server:port?...#remarks` URL.
+///
+/// Query values and the remarks fragment are percent-encoded on the way out and
+/// correspondingly decoded by [`parse_vless_config`], so that a
+/// parse→serialize→parse cycle reproduces the original config even when values
+/// contain `#`, `&`, `=`, or spaces. Only the parameters actually present on
+/// `config` are emitted.
+pub fn to_vless_url(config: &VlessConfig) -> String {
+    let mut params: Vec<(&str, String)> = Vec::new();
+    if let Some(network) = &config.network {
+        params.push(("type", network.clone()));
+    }
+    if let Some(encryption) = &config.encryption {
+        params.push(("encryption", encryption.clone()));
+    }
+    if let Some(transport) = &config.transport {
+        if let Some(host) = &transport.host {
+            params.push(("host", host.clone()));
+        }
+        if let Some(path) = &transport.path {
+            params.push(("path", path.clone()));
+        }
+        if let Some(header) = &transport.header {
+            params.push(("headerType", header.r#type.clone()));
+        }
+    }
+    if let Some(security) = &config.security {
+        params.push(("security", security.clone()));
+    }
+    if let Some(flow) = &config.flow {
+        params.push(("flow", flow.clone()));
+    }
+    if let Some(fp) = &config.fingerprint {
+        params.push(("fp", fp.clone()));
+    }
+    if let Some(pbk) = &config.pbk {
+        params.push(("pbk", pbk.clone()));
+    }
+    if let Some(sid) = &config.sid {
+        params.push(("sid", sid.clone()));
+    }
+    if let Some(sni) = &config.sni {
+        params.push(("sni", sni.clone()));
+    }
+    if let Some(service_name) = &config.service_name {
+        params.push(("serviceName", service_name.clone()));
+    }
+    if let Some(packet_encoding) = &config.packet_encoding {
+        params.push(("packetEncoding", packet_encoding.clone()));
+    }
+    params.push(("insecure", if config.insecure { "1" } else { "0" }.to_string()));
+
+    let query = params
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, urlencoding::encode(value)))
+        .collect::<Vec<_>>()
+        .join("&");
+
+    let mut url = format!(
+        "vless://{}@{}:{}?{}",
+        config.uuid, config.server, config.server_port, query
+    );
+    if let Some(remarks) = &config.remarks {
+        url.push('#');
+        url.push_str(&urlencoding::encode(remarks));
+    }
+    url
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -352,6 +428,37 @@ mod tests {
         assert_eq!(config.remarks, Some("NL-A-xTom-0.5%E5%80%8D%E7%8E%87".to_string()));
     }
 
+    #[test]
+    fn test_to_vless_url_round_trips() {
+        let vless_url = "vless://6a49f6c2-8b2f-4eee-a9ee-9a016e300edb@nl01.ctcxianyu.com:10010?type=tcp&encryption=none&host=&path=&headerType=none&quicSecurity=none&serviceName=&security=reality&flow=xtls-rprx-vision&fp=firefox&insecure=0&sni=d1--ov-gotcha07.bilivideo.com&pbk=43xDvHER1zvWFv3OHjLb6U_t4OcWbpY9moxxZ8UltCM&sid=6ba85179e30d4ff7#NL-A-xTom-0.5%E5%80%8D%E7%8E%87";
+        let config = parse_vless_config(vless_url).unwrap();
+        let reparsed = parse_vless_config(&to_vless_url(&config)).unwrap();
+
+        assert_eq!(reparsed.server, config.server);
+        assert_eq!(reparsed.server_port, config.server_port);
+        assert_eq!(reparsed.uuid, config.uuid);
+        assert_eq!(reparsed.flow, config.flow);
+        assert_eq!(reparsed.fingerprint, config.fingerprint);
+        assert_eq!(reparsed.security, config.security);
+        assert_eq!(reparsed.pbk, config.pbk);
+        assert_eq!(reparsed.sid, config.sid);
+        assert_eq!(reparsed.sni, config.sni);
+        assert_eq!(reparsed.remarks, config.remarks);
+    }
+
+    #[test]
+    fn test_to_vless_url_round_trips_ws_transport() {
+        let vless_url = "vless://6a49f6c2-8b2f-4eee-a9ee-9a016e300edb@example.com:443?type=ws&encryption=none&host=cdn.example.com&path=%2Fray&headerType=http&security=tls&sni=cdn.example.com#ws-node";
+        let config = parse_vless_config(vless_url).unwrap();
+        let reparsed = parse_vless_config(&to_vless_url(&config)).unwrap();
+
+        let transport = reparsed.transport.expect("transport present");
+        assert_eq!(transport.r#type, "ws");
+        assert_eq!(transport.host, Some("cdn.example.com".to_string()));
+        assert_eq!(transport.path, Some("/ray".to_string()));
+        assert_eq!(transport.header.map(|h| h.r#type), Some("http".to_string()));
+    }
+
     #[test]
     fn test_extract_uuid() {
         let uuid = "6a49f6c2-8b2f-4eee-a9ee-9a016e300edb";
@@ -1,31 +1,90 @@
+use crate::nodes::host::Host;
 use base64::{Engine as _, engine::general_purpose};
+use serde::de::{self, Deserializer, Visitor};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 /// Configuration for Vmess protocol nodes
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Every field is tolerant of real-world links: optional keys carry
+/// `#[serde(default)]`, numeric `port`/`aid` are accepted as either strings or
+/// integers, and newer keys (`scy`, `sni`, `alpn`, `fp`) are preserved instead
+/// of making deserialization fail.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct VmessConfig {
-    #[serde(rename = "v")]
+    #[serde(rename = "v", default)]
     pub version: String,
-    #[serde(rename = "ps")]
+    #[serde(rename = "ps", default)]
     pub remarks: String,
-    #[serde(rename = "add")]
+    #[serde(rename = "add", default)]
     pub address: String,
-    #[serde(rename = "port")]
+    #[serde(rename = "port", default, deserialize_with = "string_or_int")]
     pub port: String,
-    #[serde(rename = "id")]
+    #[serde(rename = "id", default)]
     pub user_id: String,
-    #[serde(rename = "aid")]
+    #[serde(rename = "aid", default, deserialize_with = "string_or_int")]
     pub alter_id: String,
-    #[serde(rename = "net")]
+    #[serde(rename = "net", default)]
     pub network: String,
-    #[serde(rename = "type")]
+    #[serde(rename = "type", default)]
     pub header_type: String,
-    #[serde(rename = "host")]
+    #[serde(rename = "host", default)]
     pub host: String,
-    #[serde(rename = "path")]
+    #[serde(rename = "path", default)]
     pub path: String,
-    #[serde(rename = "tls")]
+    #[serde(rename = "tls", default)]
     pub tls: String,
+    #[serde(rename = "scy", default, skip_serializing_if = "Option::is_none")]
+    pub security: Option<String>,
+    #[serde(rename = "sni", default, skip_serializing_if = "Option::is_none")]
+    pub sni: Option<String>,
+    #[serde(rename = "alpn", default, skip_serializing_if = "Option::is_none")]
+    pub alpn: Option<String>,
+    #[serde(rename = "fp", default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+    /// Classified `add`ress (IPv4/IPv6/domain), filled in after deserialization.
+    #[serde(skip)]
+    pub parsed_host: Option<Host>,
+}
+
+/// Deserializes a field that providers encode as either a JSON string or a
+/// JSON number (commonly `port` and `aid`) into a `String`.
+fn string_or_int<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct StringOrInt;
+
+    impl<'de> Visitor<'de> for StringOrInt {
+        type Value = String;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a string or an integer")
+        }
+
+        fn visit_str<E>(self, value: &str) -> Result<String, E>
+        where
+            E: de::Error,
+        {
+            Ok(value.to_string())
+        }
+
+        fn visit_u64<E>(self, value: u64) -> Result<String, E>
+        where
+            E: de::Error,
+        {
+            Ok(value.to_string())
+        }
+
+        fn visit_i64<E>(self, value: i64) -> Result<String, E>
+        where
+            E: de::Error,
+        {
+            Ok(value.to_string())
+        }
+    }
+
+    deserializer.deserialize_any(StringOrInt)
 }
 
 /// Parses Vmess configuration from a vmess:// URL
@@ -49,7 +108,7 @@ pub fn parse_vmess_config(vmess_url: &str) -> Option<VmessConfig> {
             match String::from_utf8(decoded_bytes) {
                 Ok(json_str) => {
                     match serde_json::from_str::<VmessConfig>(&json_str) {
-                        Ok(config) => Some(config),
+                        Ok(config) => finalize(config),
                         Err(_) => None,
                     }
                 }
@@ -63,7 +122,7 @@ pub fn parse_vmess_config(vmess_url: &str) -> Option<VmessConfig> {
                     match String::from_utf8(decoded_bytes) {
                         Ok(json_str) => {
                             match serde_json::from_str::<VmessConfig>(&json_str) {
-                                Ok(config) => Some(config),
+                                Ok(config) => finalize(config),
                                 Err(_) => None,
                             }
                         }
@@ -76,6 +135,43 @@ pub fn parse_vmess_config(vmess_url: &str) -> Option<VmessConfig> {
     }
 }
 
+impl VmessConfig {
+    /// Re-serializes the struct to JSON and base64-encodes it back into a
+    /// `vmess://…` link, the inverse of [`parse_vmess_config`]. The derived
+    /// `parsed_host` field is `#[serde(skip)]`, so it is recomputed on the way back in.
+    pub fn to_url(&self) -> String {
+        let json = serde_json::to_string(self).unwrap_or_default();
+        format!("vmess://{}", general_purpose::STANDARD.encode(json))
+    }
+}
+
+/// Classifies the `add`ress and rejects links whose port is out of range,
+/// returning `None` for an unusable node rather than a half-parsed config.
+fn finalize(mut config: VmessConfig) -> Option<VmessConfig> {
+    if config.port.parse::<u16>().map(|p| p == 0).unwrap_or(true) {
+        return None;
+    }
+    config.parsed_host = Host::parse(&config.address);
+    Some(config)
+}
+
+/// Validates a recovered Vmess config: a UUID `id`, a parseable non-zero port,
+/// and a recognised transport in `net`.
+pub fn validate_vmess_config(config: &VmessConfig) -> bool {
+    is_valid_uuid(&config.user_id)
+        && config.port.parse::<u16>().map(|p| p > 0).unwrap_or(false)
+        && matches!(
+            config.network.as_str(),
+            "tcp" | "ws" | "grpc" | "h2" | "kcp" | "quic" | "http"
+        )
+}
+
+/// Basic UUID validation (hex digits with optional hyphens).
+fn is_valid_uuid(uuid: &str) -> bool {
+    (uuid.len() == 32 || uuid.len() == 36)
+        && uuid.chars().all(|c| c.is_ascii_hexdigit() || c == '-')
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -98,4 +194,30 @@ mod tests {
             None => panic!("Failed to parse vmess config"),
         }
     }
+
+    #[test]
+    fn test_parse_tolerates_numeric_port_and_missing_fields() {
+        // Numeric `port`/`aid` and a newer `scy` key, with several legacy
+        // fields omitted entirely.
+        let config_json = r#"{"v":"2","ps":"n","add":"example.com","port":443,"id":"12345678-1234-1234-1234-123456789abc","aid":0,"net":"ws","scy":"auto"}"#;
+        let encoded_config = general_purpose::STANDARD.encode(config_json);
+        let vmess_url = format!("vmess://{}", encoded_config);
+
+        let config = parse_vmess_config(&vmess_url).unwrap();
+        assert_eq!(config.port, "443");
+        assert_eq!(config.alter_id, "0");
+        assert_eq!(config.security, Some("auto".to_string()));
+        assert!(validate_vmess_config(&config));
+    }
+
+    #[test]
+    fn test_to_url_round_trip() {
+        let config_json = r#"{"v":"2","ps":"test-node","add":"example.com","port":"443","id":"12345678-1234-1234-1234-123456789abc","aid":"0","net":"ws","type":"none","host":"","path":"/","tls":""}"#;
+        let encoded_config = general_purpose::STANDARD.encode(config_json);
+        let vmess_url = format!("vmess://{}", encoded_config);
+
+        let config = parse_vmess_config(&vmess_url).unwrap();
+        let reparsed = parse_vmess_config(&config.to_url()).unwrap();
+        assert_eq!(config, reparsed);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,170 @@
+use std::error::Error;
+use std::time::Duration;
+
+use crate::base64_decoder::decode_to_string;
+use crate::nodes::{parse_node, parse_ss_config, NodeConfig, ShadowsocksConfig};
+
+/// Options controlling how a remote subscription is fetched.
+#[derive(Debug, Clone)]
+pub struct FetchOptions {
+    /// `User-Agent` sent with the request (many providers gate on this).
+    pub user_agent: String,
+    /// Overall request timeout.
+    pub timeout: Duration,
+    /// Optional upstream proxy (e.g. `socks5://127.0.0.1:1080`).
+    pub proxy: Option<String>,
+    /// Accept invalid TLS certificates, mirroring the per-node `allowInsecure`.
+    pub allow_insecure: bool,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self {
+            user_agent: "ClashMetaForAndroid/2.11.19".to_string(),
+            timeout: Duration::from_secs(30),
+            proxy: None,
+            allow_insecure: false,
+        }
+    }
+}
+
+/// Downloads a subscription URL and expands it into a list of parsed nodes.
+///
+/// The body is expected to be a base64-encoded, newline-separated list of node
+/// URLs; if it is not valid base64 it is treated as the plain list. Each line is
+/// run through [`parse_node`] and lines that fail to parse are reported to stderr
+/// rather than aborting the whole fetch.
+pub async fn fetch_subscription(
+    url: &str,
+    opts: FetchOptions,
+) -> Result<Vec<NodeConfig>, Box<dyn Error>> {
+    let mut builder = reqwest::Client::builder()
+        .user_agent(&opts.user_agent)
+        .timeout(opts.timeout);
+
+    if let Some(proxy) = &opts.proxy {
+        builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+    }
+    if opts.allow_insecure {
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+
+    let client = builder.build()?;
+    let body = client.get(url).send().await?.error_for_status()?.text().await?;
+
+    // Providers usually base64-encode the list; fall back to the raw body when
+    // it is already plain text.
+    let decoded = decode_to_string(&body).unwrap_or(body);
+
+    let mut nodes = Vec::new();
+    let mut failures = Vec::new();
+    for (index, line) in decoded.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match parse_node(line) {
+            Ok(node) => nodes.push(node),
+            Err(err) => failures.push((index + 1, err)),
+        }
+    }
+
+    for (lineno, err) in &failures {
+        eprintln!("subscription {}: line {} skipped: {}", url, lineno, err);
+    }
+
+    Ok(nodes)
+}
+
+/// Upstream proxy used to reach a subscription endpoint.
+#[derive(Debug, Clone)]
+pub enum ProxyConfig {
+    /// Connect directly.
+    None,
+    /// Route every request through a single proxy URL (`socks5://…`, `http://…`).
+    Global { url: String },
+    /// Route only the listed domains through their respective proxies.
+    ByDomain(Vec<PartialProxyConfig>),
+}
+
+/// A per-domain proxy binding used by [`ProxyConfig::ByDomain`].
+#[derive(Debug, Clone)]
+pub struct PartialProxyConfig {
+    pub domain: String,
+    pub url: String,
+}
+
+/// Remaining-traffic info parsed from the `Subscription-Userinfo` header.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SubscriptionInfo {
+    pub upload: u64,
+    pub download: u64,
+    pub total: u64,
+    pub expire: u64,
+}
+
+/// Downloads a Shadowsocks subscription through the given upstream proxy,
+/// decodes the base64 body, and parses each line with [`parse_ss_config`].
+///
+/// The provider's `Subscription-Userinfo` header (upload/download/total/expire)
+/// is returned alongside the nodes so callers can surface remaining traffic.
+pub async fn fetch_ss_subscription(
+    url: &str,
+    proxy: ProxyConfig,
+) -> Result<(Vec<ShadowsocksConfig>, Option<SubscriptionInfo>), Box<dyn Error>> {
+    let mut builder = reqwest::Client::builder().user_agent("ClashMetaForAndroid/2.11.19");
+
+    match proxy {
+        ProxyConfig::None => {}
+        ProxyConfig::Global { url } => {
+            builder = builder.proxy(reqwest::Proxy::all(&url)?);
+        }
+        ProxyConfig::ByDomain(bindings) => {
+            builder = builder.proxy(reqwest::Proxy::custom(move |target| {
+                bindings
+                    .iter()
+                    .find(|binding| target.host_str() == Some(binding.domain.as_str()))
+                    .and_then(|binding| binding.url.parse().ok())
+            }));
+        }
+    }
+
+    let client = builder.build()?;
+    let response = client.get(url).send().await?.error_for_status()?;
+
+    let info = response
+        .headers()
+        .get("subscription-userinfo")
+        .and_then(|value| value.to_str().ok())
+        .map(parse_subscription_userinfo);
+
+    let body = response.text().await?;
+    let decoded = decode_to_string(&body).unwrap_or(body);
+
+    let nodes = decoded
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .filter_map(parse_ss_config)
+        .collect();
+
+    Ok((nodes, info))
+}
+
+/// Parses a `upload=..; download=..; total=..; expire=..` header value.
+fn parse_subscription_userinfo(value: &str) -> SubscriptionInfo {
+    let mut info = SubscriptionInfo::default();
+    for field in value.split(';') {
+        if let Some((key, val)) = field.split_once('=') {
+            let number = val.trim().parse().unwrap_or(0);
+            match key.trim() {
+                "upload" => info.upload = number,
+                "download" => info.download = number,
+                "total" => info.total = number,
+                "expire" => info.expire = number,
+                _ => {}
+            }
+        }
+    }
+    info
+}
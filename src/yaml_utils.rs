@@ -30,6 +30,10 @@ pub struct ProxyGroup {
 }
 
 impl ProxyGroup {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     pub fn from_country(country: &str) -> Self {
         ProxyGroup {
             name: country.to_string(),
@@ -133,127 +137,478 @@ pub fn create_sample_config() -> Config {
     }
 }
 
-pub fn merge_proxies(configs: Vec<Config>) -> Vec<Proxy> {
-    configs
-        .into_iter()
-        .flat_map(|config| config.proxies)
-        .collect()
+/// Matches a request host against a rule, either exactly or by glob pattern.
+#[derive(Debug, Clone)]
+pub enum HostMatcher {
+    Exact(String),
+    Pattern(glob::Pattern),
+}
+
+impl HostMatcher {
+    /// Builds a matcher from a description, choosing `Pattern` when the string
+    /// carries any glob metacharacter and `Exact` otherwise.
+    pub fn new(desc: &str) -> Self {
+        if desc.contains(['*', '?', '[', ']']) {
+            match glob::Pattern::new(desc) {
+                Ok(pattern) => HostMatcher::Pattern(pattern),
+                Err(_) => HostMatcher::Exact(desc.to_string()),
+            }
+        } else {
+            HostMatcher::Exact(desc.to_string())
+        }
+    }
+
+    pub fn matches(&self, host: &str) -> bool {
+        match self {
+            HostMatcher::Exact(exact) => exact == host,
+            HostMatcher::Pattern(pattern) => pattern.matches(host),
+        }
+    }
+}
+
+/// A single routing rule: hosts matching `matcher` are sent to `target_group`.
+#[derive(Debug, Clone)]
+pub struct RoutingRule {
+    pub matcher: HostMatcher,
+    pub target_group: String,
+}
+
+/// Renders routing rules into Clash `rules:` strings, appending a `MATCH`
+/// catch-all that points at `fallback_group`.
+///
+/// Each `target_group` (including the fallback) must name a group produced by
+/// [`create_groups_by_country`]; an unknown target is returned as an error.
+pub fn generate_rules(
+    rules: &[RoutingRule],
+    groups: &[ProxyGroup],
+    fallback_group: &str,
+) -> Result<Vec<String>, String> {
+    let known: std::collections::HashSet<&str> = groups.iter().map(|g| g.name()).collect();
+
+    let mut out = Vec::new();
+    for rule in rules {
+        if !known.contains(rule.target_group.as_str()) {
+            return Err(format!("unknown target group: {}", rule.target_group));
+        }
+        out.push(clash_rule_line(&rule.matcher, &rule.target_group));
+    }
+
+    if !known.contains(fallback_group) {
+        return Err(format!("unknown fallback group: {}", fallback_group));
+    }
+    out.push(format!("MATCH,{}", fallback_group));
+
+    Ok(out)
+}
+
+/// Maps a matcher to the most specific Clash rule type it expresses.
+fn clash_rule_line(matcher: &HostMatcher, group: &str) -> String {
+    match matcher {
+        HostMatcher::Exact(host) => format!("DOMAIN,{},{}", host, group),
+        HostMatcher::Pattern(pattern) => {
+            let raw = pattern.as_str();
+            if let Some(suffix) = raw.strip_prefix("*.") {
+                format!("DOMAIN-SUFFIX,{},{}", suffix, group)
+            } else {
+                let keyword = raw.trim_matches('*');
+                format!("DOMAIN-KEYWORD,{},{}", keyword, group)
+            }
+        }
+    }
+}
+
+/// A problem found by [`validate_config`] in a merged config.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigProblem {
+    /// A proxy is missing a field required by its `type`.
+    MissingField { proxy: String, field: String },
+    /// Two proxies share the same name after renaming.
+    DuplicateName(String),
+    /// A proxy group lists a proxy that does not exist.
+    GroupMissingProxy { group: String, proxy: String },
+    /// A rule routes to a group/proxy that is not defined.
+    RuleUnknownTarget { rule: String, target: String },
+}
+
+impl std::fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigProblem::MissingField { proxy, field } => {
+                write!(f, "proxy {} is missing required field `{}`", proxy, field)
+            }
+            ConfigProblem::DuplicateName(name) => write!(f, "duplicate proxy name: {}", name),
+            ConfigProblem::GroupMissingProxy { group, proxy } => {
+                write!(f, "group {} references unknown proxy {}", group, proxy)
+            }
+            ConfigProblem::RuleUnknownTarget { rule, target } => {
+                write!(f, "rule `{}` routes to unknown target {}", rule, target)
+            }
+        }
+    }
+}
+
+/// Clash policy targets that are always valid, regardless of declared groups.
+const BUILTIN_TARGETS: &[&str] = &["DIRECT", "REJECT", "PASS", "GLOBAL"];
+
+/// Trailing rule tokens that are options rather than a policy target.
+const RULE_OPTIONS: &[&str] = &["no-resolve", "src"];
+
+/// Returns a non-empty string property of `proxy`, if present.
+fn proxy_field(proxy: &Proxy, key: &str) -> Option<String> {
+    proxy.properties.get(key).and_then(|v| match v {
+        serde_yaml::Value::String(s) if !s.is_empty() => Some(s.clone()),
+        serde_yaml::Value::Null => None,
+        other => Some(serde_yaml::to_string(other).unwrap_or_default().trim().to_string()),
+    })
+}
+
+/// Validates a merged [`Config`], returning every problem found.
+///
+/// Checks that proxies carry the fields required by their `type` (`server`,
+/// `port`, plus `uuid` for Vmess/VLESS), that proxy names are unique, that proxy
+/// groups only reference existing proxies or groups, and that every rule routes
+/// to a defined group/proxy or a built-in target.
+pub fn validate_config(config: &Config) -> Vec<ConfigProblem> {
+    let mut problems = Vec::new();
+
+    // Required fields and duplicate names.
+    let mut seen = std::collections::HashSet::new();
+    for proxy in &config.proxies {
+        if !seen.insert(proxy.name.clone()) {
+            problems.push(ConfigProblem::DuplicateName(proxy.name.clone()));
+        }
+        for field in ["server", "port"] {
+            if proxy_field(proxy, field).is_none() {
+                problems.push(ConfigProblem::MissingField {
+                    proxy: proxy.name.clone(),
+                    field: field.to_string(),
+                });
+            }
+        }
+        let proxy_type = proxy_field(proxy, "type").unwrap_or_default();
+        if matches!(proxy_type.as_str(), "vmess" | "vless")
+            && proxy_field(proxy, "uuid").is_none()
+        {
+            problems.push(ConfigProblem::MissingField {
+                proxy: proxy.name.clone(),
+                field: "uuid".to_string(),
+            });
+        }
+    }
+
+    // The set of names a group or rule may legitimately point at.
+    let proxy_names: std::collections::HashSet<&str> =
+        config.proxies.iter().map(|p| p.name.as_str()).collect();
+    let group_names: std::collections::HashSet<&str> =
+        config.proxy_groups.iter().map(|g| g.name()).collect();
+    let is_known = |target: &str| {
+        proxy_names.contains(target)
+            || group_names.contains(target)
+            || BUILTIN_TARGETS.contains(&target)
+    };
+
+    for group in &config.proxy_groups {
+        for member in &group.proxies {
+            if !is_known(member) {
+                problems.push(ConfigProblem::GroupMissingProxy {
+                    group: group.name().to_string(),
+                    proxy: member.clone(),
+                });
+            }
+        }
+    }
+
+    for rule in &config.rules {
+        let fields: Vec<&str> = rule.split(',').map(str::trim).collect();
+        // The policy target is the last field that is not a trailing option.
+        let target = fields
+            .iter()
+            .rev()
+            .find(|field| !RULE_OPTIONS.contains(*field))
+            .copied();
+        if let Some(target) = target {
+            if !is_known(target) {
+                problems.push(ConfigProblem::RuleUnknownTarget {
+                    rule: rule.clone(),
+                    target: target.to_string(),
+                });
+            }
+        }
+    }
+
+    problems
+}
+
+/// One source contributing proxies and rules to the merge, tagged with an
+/// `origin` label (filename plus index) so the merged result can report where
+/// each proxy or rule came from.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigLayer {
+    pub origin: String,
+    pub proxies: Vec<Proxy>,
+    pub rules: Vec<String>,
+}
+
+impl ConfigLayer {
+    /// Wraps a parsed [`Config`] as a layer with the given origin label.
+    pub fn from_config(origin: impl Into<String>, config: Config) -> Self {
+        ConfigLayer {
+            origin: origin.into(),
+            proxies: config.proxies,
+            rules: config.rules,
+        }
+    }
+}
+
+/// Directives read from an optional user override layer.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OverrideDirectives {
+    /// Names or glob patterns whose matching proxies/rules are removed.
+    #[serde(default)]
+    pub unset: Vec<String>,
+    /// Extra proxy/rule files (relative to the config dir) to pull in.
+    #[serde(default)]
+    pub include: Vec<String>,
+}
+
+/// The outcome of a layered merge, carrying the resolved proxies and rules plus
+/// the origin layer that supplied each of them.
+#[derive(Debug, Clone, Default)]
+pub struct MergedConfig {
+    pub proxies: Vec<Proxy>,
+    pub rules: Vec<String>,
+    /// `(proxy name, origin)` in output order.
+    proxy_origins: Vec<(String, String)>,
+    /// `(rule, origin)` in output order.
+    rule_origins: Vec<(String, String)>,
+}
+
+impl MergedConfig {
+    /// Renders a human-readable provenance report, one line per proxy and rule.
+    pub fn provenance_dump(&self) -> String {
+        let mut out = String::new();
+        for (name, origin) in &self.proxy_origins {
+            out.push_str(&format!("proxy {} <- {}\n", name, origin));
+        }
+        for (rule, origin) in &self.rule_origins {
+            out.push_str(&format!("rule {} <- {}\n", rule, origin));
+        }
+        out
+    }
+}
+
+/// Returns whether `value` matches `selector`, which is either a literal name or
+/// a glob pattern.
+fn selector_matches(selector: &str, value: &str) -> bool {
+    match glob::Pattern::new(selector) {
+        Ok(pattern) => pattern.matches(value),
+        Err(_) => selector == value,
+    }
+}
+
+/// Merges `layers` (highest precedence first) into a single [`MergedConfig`].
+///
+/// A proxy name or rule contributed by a higher-precedence layer shadows the
+/// same name/rule from lower layers, replacing the silent de-duplication the
+/// pipeline used to do. Any proxy or rule matching one of the `unset`
+/// selectors (a name or glob pattern) is dropped regardless of layer.
+pub fn merge_layers(layers: &[ConfigLayer], unset: &[String]) -> MergedConfig {
+    let mut merged = MergedConfig::default();
+    let mut seen_proxies = std::collections::HashSet::new();
+    let mut seen_rules = std::collections::HashSet::new();
+
+    for layer in layers {
+        for proxy in &layer.proxies {
+            if seen_proxies.contains(&proxy.name)
+                || unset.iter().any(|sel| selector_matches(sel, &proxy.name))
+            {
+                continue;
+            }
+            seen_proxies.insert(proxy.name.clone());
+            merged
+                .proxy_origins
+                .push((proxy.name.clone(), layer.origin.clone()));
+            merged.proxies.push(proxy.clone());
+        }
+
+        for rule in &layer.rules {
+            if seen_rules.contains(rule) || unset.iter().any(|sel| selector_matches(sel, rule)) {
+                continue;
+            }
+            seen_rules.insert(rule.clone());
+            merged.rule_origins.push((rule.clone(), layer.origin.clone()));
+            merged.rules.push(rule.clone());
+        }
+    }
+
+    merged
+}
+
+/// A single region bucket: proxies whose name contains one of `keywords` or
+/// matches one of `patterns` are grouped under `group_name`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CountryRule {
+    pub group_name: String,
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    #[serde(default, with = "glob_patterns")]
+    pub patterns: Vec<glob::Pattern>,
 }
 
-pub fn merge_rules(rules: Vec<Config>) -> Vec<String> {
-    rules
-        .into_iter()
-        .flat_map(|config| config.rules)
-        .collect()
+impl CountryRule {
+    fn matches(&self, name: &str) -> bool {
+        self.keywords.iter().any(|kw| name.contains(kw))
+            || self.patterns.iter().any(|p| p.matches(name))
+    }
 }
 
+/// Template for a selector group emitted alongside the region groups.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectorTemplate {
+    pub name: String,
+    pub r#type: String,
+    /// Entries placed before the region groups (e.g. `DIRECT`).
+    #[serde(default)]
+    pub prepend: Vec<String>,
+    /// Whether to append every region group (plus `Other`) to this selector.
+    #[serde(default)]
+    pub append_countries: bool,
+}
+
+/// Data-driven classification config, loadable from YAML.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassificationConfig {
+    pub countries: Vec<CountryRule>,
+    pub selectors: Vec<SelectorTemplate>,
+    /// Keywords (e.g. `剩余`/`到期`) that keep a proxy matching no country rule
+    /// out of the `Other` group; a proxy that matches a country rule is still
+    /// placed in that country's group.
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+impl ClassificationConfig {
+    /// Loads a classification config from YAML.
+    pub fn from_yaml(yaml: &str) -> Result<ClassificationConfig, serde_yaml::Error> {
+        serde_yaml::from_str(yaml)
+    }
+}
+
+impl Default for ClassificationConfig {
+    fn default() -> Self {
+        let country = |group: &str, keywords: &[&str]| CountryRule {
+            group_name: group.to_string(),
+            keywords: keywords.iter().map(|s| s.to_string()).collect(),
+            patterns: vec![],
+        };
+        let selector = |name: &str, prepend: &[&str]| SelectorTemplate {
+            name: name.to_string(),
+            r#type: "select".to_string(),
+            prepend: prepend.iter().map(|s| s.to_string()).collect(),
+            append_countries: true,
+        };
+
+        ClassificationConfig {
+            countries: vec![
+                country("Germany", &["德国", "DE"]),
+                country("Taiwan", &["台湾", "TW"]),
+                country("Hong Kong", &["香港", "HK"]),
+                country("Japan", &["日本", "JP"]),
+                country("Singapore", &["新加坡", "SG"]),
+                country("US", &["美国", "US"]),
+                country("UK", &["英国", "UK"]),
+            ],
+            selectors: vec![
+                selector("手动选择", &[]),
+                selector("Google", &[]),
+                selector("Microsoft", &["DIRECT"]),
+                selector("Apple", &["DIRECT"]),
+            ],
+            exclude: vec!["剩余".to_string(), "到期".to_string()],
+        }
+    }
+}
+
+/// Serde helper to (de)serialize `Vec<glob::Pattern>` as a list of strings.
+mod glob_patterns {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S>(patterns: &[glob::Pattern], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let raw: Vec<&str> = patterns.iter().map(|p| p.as_str()).collect();
+        raw.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<glob::Pattern>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = Vec::<String>::deserialize(deserializer)?;
+        raw.into_iter()
+            .map(|s| glob::Pattern::new(&s).map_err(serde::de::Error::custom))
+            .collect()
+    }
+}
+
+/// Groups proxies by country using the built-in [`ClassificationConfig`].
 pub fn create_groups_by_country(proxies: &Vec<Proxy>) -> Vec<ProxyGroup> {
-    let mut de = ProxyGroup::from_country("Germany");
-    let mut tw = ProxyGroup::from_country("Taiwan");
-    let mut hk = ProxyGroup::from_country("Hong Kong");
-    let mut jp = ProxyGroup::from_country("Japan");
-    let mut sg = ProxyGroup::from_country("Singapore");
-    let mut us = ProxyGroup::from_country("US");
-    let mut uk = ProxyGroup::from_country("UK");
+    create_groups_with_config(proxies, &ClassificationConfig::default())
+}
+
+/// Groups proxies by country using a caller-supplied classification config.
+pub fn create_groups_with_config(
+    proxies: &Vec<Proxy>,
+    config: &ClassificationConfig,
+) -> Vec<ProxyGroup> {
+    let mut country_groups: Vec<ProxyGroup> = config
+        .countries
+        .iter()
+        .map(|rule| ProxyGroup::from_country(&rule.group_name))
+        .collect();
     let mut others = ProxyGroup::from_country("Other");
 
     for Proxy { name, .. } in proxies {
-        if name.contains("德国") || name.contains("DE") {
-            de.proxies.push(name.clone());
-            continue;
-        } else if name.contains("台湾") || name.contains("TW") {
-            tw.proxies.push(name.clone());
-            continue;
-        } else if name.contains("香港") || name.contains("HK") {
-            hk.proxies.push(name.clone());
-            continue;
-        } else if name.contains("日本") || name.contains("JP") {
-            jp.proxies.push(name.clone());
-            continue;
-        } else if name.contains("新加坡") || name.contains("SG") {
-            sg.proxies.push(name.clone());
-            continue;
-        } else if name.contains("美国") || name.contains("US") {
-            us.proxies.push(name.clone());
-            continue;
-        } else if name.contains("英国") || name.contains("UK") {
-            uk.proxies.push(name.clone());
-            continue;
-        } else if name.contains("剩余") || name.contains("到期") {
+        if let Some(index) = config.countries.iter().position(|rule| rule.matches(name)) {
+            country_groups[index].proxies.push(name.clone());
+        } else if config.exclude.iter().any(|kw| name.contains(kw)) {
             continue;
         } else {
             others.proxies.push(name.clone());
-            continue;
         }
     }
-    let select = ProxyGroup {
-        name: "手动选择".to_string(),
-        r#type: "select".to_string(),
-        proxies: vec![
-            "Germany",
-            "Taiwan",
-            "Hong Kong",
-            "Japan",
-            "Singapore",
-            "US",
-            "UK",
-            "Other",
-        ].iter().map(|s| s.to_string()).collect(),
-        timeout: None,
-        interval: None,
-    };
-    let ms = ProxyGroup {
-        name: "Microsoft".to_string(),
-        r#type: "select".to_string(),
-        proxies: vec![
-            "DIRECT",
-            "Germany",
-            "Taiwan",
-            "Hong Kong",
-            "Japan",
-            "Singapore",
-            "US",
-            "UK",
-            "Other",
-        ].iter().map(|s| s.to_string()).collect(),
-        timeout: None,
-        interval: None,
-    };
-    let apple = ProxyGroup {
-        name: "Apple".to_string(),
-        r#type: "select".to_string(),
-        proxies: vec![
-            "DIRECT",
-            "Germany",
-            "Taiwan",
-            "Hong Kong",
-            "Japan",
-            "Singapore",
-            "US",
-            "UK",
-            "Other",
-        ].iter().map(|s| s.to_string()).collect(),
-        timeout: None,
-        interval: None,
-    };
-    let google = ProxyGroup {
-        name: "Google".to_string(),
-        r#type: "select".to_string(),
-        proxies: vec![
-            "Germany",
-            "Taiwan",
-            "Hong Kong",
-            "Japan",
-            "Singapore",
-            "US",
-            "UK",
-            "Other",
-        ].iter().map(|s| s.to_string()).collect(),
-        timeout: None,
-        interval: None,
-    };
-    vec![select,google,ms,apple, de, tw, hk, jp, sg, us, uk, others]
+
+    let country_names: Vec<String> = config
+        .countries
+        .iter()
+        .map(|rule| rule.group_name.clone())
+        .chain(std::iter::once("Other".to_string()))
+        .collect();
+
+    let mut groups: Vec<ProxyGroup> = config
+        .selectors
+        .iter()
+        .map(|tmpl| {
+            let mut proxies = tmpl.prepend.clone();
+            if tmpl.append_countries {
+                proxies.extend(country_names.clone());
+            }
+            ProxyGroup {
+                name: tmpl.name.clone(),
+                r#type: tmpl.r#type.clone(),
+                proxies,
+                timeout: None,
+                interval: None,
+            }
+        })
+        .collect();
+
+    groups.extend(country_groups);
+    groups.push(others);
+    groups
 }
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -306,6 +661,126 @@ proxies:
         assert!(config.properties.contains_key("servers"));
     }
 
+    #[test]
+    fn test_default_classification() {
+        let proxies = vec![
+            Proxy { name: "香港 01".to_string(), properties: HashMap::new() },
+            Proxy { name: "剩余流量".to_string(), properties: HashMap::new() },
+            Proxy { name: "mystery".to_string(), properties: HashMap::new() },
+        ];
+        let groups = create_groups_by_country(&proxies);
+        let hk = groups.iter().find(|g| g.name() == "Hong Kong").unwrap();
+        assert_eq!(hk.proxies, vec!["香港 01".to_string()]);
+        let other = groups.iter().find(|g| g.name() == "Other").unwrap();
+        assert_eq!(other.proxies, vec!["mystery".to_string()]);
+    }
+
+    #[test]
+    fn test_host_matcher() {
+        assert!(HostMatcher::new("example.com").matches("example.com"));
+        assert!(!HostMatcher::new("example.com").matches("other.com"));
+        assert!(HostMatcher::new("*.example.com").matches("a.example.com"));
+    }
+
+    #[test]
+    fn test_generate_rules() {
+        let groups = create_groups_by_country(&vec![]);
+        let rules = vec![
+            RoutingRule {
+                matcher: HostMatcher::new("*.google.com"),
+                target_group: "Google".to_string(),
+            },
+            RoutingRule {
+                matcher: HostMatcher::new("apple.com"),
+                target_group: "Apple".to_string(),
+            },
+        ];
+        let lines = generate_rules(&rules, &groups, "手动选择").unwrap();
+        assert_eq!(lines[0], "DOMAIN-SUFFIX,google.com,Google");
+        assert_eq!(lines[1], "DOMAIN,apple.com,Apple");
+        assert_eq!(lines[2], "MATCH,手动选择");
+    }
+
+    #[test]
+    fn test_generate_rules_rejects_unknown_group() {
+        let groups = create_groups_by_country(&vec![]);
+        let rules = vec![RoutingRule {
+            matcher: HostMatcher::new("apple.com"),
+            target_group: "Nope".to_string(),
+        }];
+        assert!(generate_rules(&rules, &groups, "手动选择").is_err());
+    }
+
+    #[test]
+    fn test_merge_layers_precedence_and_provenance() {
+        let high = ConfigLayer {
+            origin: "subscription[0]".to_string(),
+            proxies: vec![Proxy { name: "香港 01".to_string(), properties: HashMap::new() }],
+            rules: vec!["MATCH,DIRECT".to_string()],
+        };
+        let low = ConfigLayer {
+            origin: "subscription[1]".to_string(),
+            proxies: vec![
+                Proxy { name: "香港 01".to_string(), properties: HashMap::new() },
+                Proxy { name: "日本 01".to_string(), properties: HashMap::new() },
+            ],
+            rules: vec!["MATCH,DIRECT".to_string()],
+        };
+
+        let merged = merge_layers(&[high, low], &[]);
+
+        // The duplicate "香港 01" resolves to the higher-precedence layer.
+        assert_eq!(merged.proxies.len(), 2);
+        assert_eq!(merged.rules.len(), 1);
+        assert!(merged.provenance_dump().contains("proxy 香港 01 <- subscription[0]"));
+        assert!(merged.provenance_dump().contains("proxy 日本 01 <- subscription[1]"));
+    }
+
+    #[test]
+    fn test_merge_layers_unset_by_glob() {
+        let layer = ConfigLayer {
+            origin: "subscription[0]".to_string(),
+            proxies: vec![
+                Proxy { name: "香港 01".to_string(), properties: HashMap::new() },
+                Proxy { name: "日本 01".to_string(), properties: HashMap::new() },
+            ],
+            rules: vec![],
+        };
+
+        let merged = merge_layers(&[layer], &["香港*".to_string()]);
+
+        assert_eq!(merged.proxies.len(), 1);
+        assert_eq!(merged.proxies[0].name, "日本 01");
+    }
+
+    #[test]
+    fn test_validate_config_reports_problems() {
+        let mut props = HashMap::new();
+        props.insert("type".to_string(), serde_yaml::Value::from("vmess"));
+        props.insert("server".to_string(), serde_yaml::Value::from("example.com"));
+        // Missing `port` and `uuid`.
+        let config = Config {
+            properties: HashMap::new(),
+            proxies: vec![Proxy { name: "A".to_string(), properties: props }],
+            proxy_groups: vec![],
+            rules: vec!["MATCH,Nope".to_string()],
+        };
+
+        let problems = validate_config(&config);
+        assert!(problems.contains(&ConfigProblem::MissingField {
+            proxy: "A".to_string(),
+            field: "port".to_string(),
+        }));
+        assert!(problems.contains(&ConfigProblem::MissingField {
+            proxy: "A".to_string(),
+            field: "uuid".to_string(),
+        }));
+        assert!(problems.contains(&ConfigProblem::RuleUnknownTarget {
+            rule: "MATCH,Nope".to_string(),
+            target: "Nope".to_string(),
+        }));
+    }
+
     #[test]
     fn test_round_trip() {
         let original_config = create_sample_config();